@@ -0,0 +1,420 @@
+use anyhow::{anyhow, Result};
+use board_game::GameActionBlob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use minigame_interface::{amount, Amount, AmountDelta, MinigameSetup, TableId};
+use sdk::caller::ExecutionContext;
+use sdk::utils::parse_calldata;
+use sdk::{
+    Blob, BlobData, BlobIndex, Calldata, ContractAction, ContractName, Identity, LaneId, RunResult,
+    StateCommitment, StructuredBlobData, ZkContract,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+pub mod utils;
+
+/// Players have this long to commit their sealed bids.
+pub const COMMIT_WINDOW_MS: u128 = 30_000;
+/// Once bids are committed, players have this long to reveal them.
+pub const REVEAL_WINDOW_MS: u128 = 30_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Bidder {
+    pub id: Identity,
+    pub name: String,
+    // The board bet, paid into the prize pot.
+    pub ante: Amount,
+    // Coins on the board, the most the player can bid.
+    pub coins: Amount,
+    pub commitment: Option<[u8; 32]>,
+    pub bid: Option<Amount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Auction {
+    pub bidders: BTreeMap<Identity, Bidder>,
+    pub commit_deadline: u128,
+    pub reveal_deadline: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GameState {
+    // One auction per board table.
+    pub auctions: BTreeMap<TableId, Auction>,
+    pub board_contract: ContractName,
+    pub backend_identity: Identity,
+    pub last_interaction_time: u128,
+    pub lane_id: LaneId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainAction {
+    InitMinigame {
+        table_id: TableId,
+        players: MinigameSetup,
+    },
+    Commit {
+        table_id: TableId,
+        player_id: Identity,
+        // See `bid_commitment`.
+        commitment: [u8; 32],
+    },
+    Reveal {
+        table_id: TableId,
+        player_id: Identity,
+        bid: Amount,
+        salt: [u8; 32],
+    },
+    Done {
+        table_id: TableId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainEvent {
+    MinigameInitialized {
+        table_id: TableId,
+        player_count: usize,
+    },
+    BidCommitted {
+        table_id: TableId,
+        player_id: Identity,
+    },
+    BidRevealed {
+        table_id: TableId,
+        player_id: Identity,
+        bid: Amount,
+    },
+    AuctionSettled {
+        table_id: TableId,
+        winner: Option<Identity>,
+        winning_bid: Amount,
+        pot: Amount,
+    },
+    MinigameEnded {
+        table_id: TableId,
+        final_results: Vec<(Identity, AmountDelta)>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ChainActionBlob(pub u128, pub ChainAction);
+
+impl ContractAction for ChainActionBlob {
+    fn as_blob(
+        &self,
+        contract_name: ContractName,
+        caller: Option<BlobIndex>,
+        callees: Option<Vec<BlobIndex>>,
+    ) -> Blob {
+        Blob {
+            contract_name,
+            data: BlobData::from(StructuredBlobData {
+                caller,
+                callees,
+                parameters: self.clone(),
+            }),
+        }
+    }
+}
+
+impl ZkContract for GameState {
+    fn execute(&mut self, contract_input: &Calldata) -> RunResult {
+        let (action, mut exec_ctx) =
+            parse_calldata::<ChainActionBlob>(contract_input).map_err(|e| e.to_string())?;
+
+        // Not an identity provider
+        if contract_input
+            .identity
+            .0
+            .ends_with(&exec_ctx.contract_name.0)
+        {
+            return Err("Invalid identity provider".to_string());
+        }
+
+        let Some(ref ctx) = contract_input.tx_ctx else {
+            return Err("Missing transaction context".into());
+        };
+
+        // Rollup mode, ensure everything is sent to the same lane ID or we are well past interaction timeout
+        let interaction_timeout = ctx.timestamp.0.saturating_add(60 * 60 * 24 * 1000); // 24 hours
+        if self.lane_id == LaneId::default() || ctx.timestamp.0 > interaction_timeout {
+            self.lane_id = ctx.lane_id.clone();
+        } else if self.lane_id != ctx.lane_id {
+            return Err("Invalid lane ID".into());
+        }
+
+        let events = self
+            .process_chain_action(
+                &contract_input.identity,
+                &action.1,
+                ctx.timestamp.0,
+                Some((&action, &mut exec_ctx)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        self.last_interaction_time = ctx.timestamp.0;
+
+        Ok((borsh::to_vec(&events).unwrap(), exec_ctx, vec![]))
+    }
+
+    fn commit(&self) -> StateCommitment {
+        StateCommitment(borsh::to_vec(self).unwrap())
+    }
+}
+
+impl ChainAction {
+    pub fn table_id(&self) -> TableId {
+        match self {
+            ChainAction::InitMinigame { table_id, .. }
+            | ChainAction::Commit { table_id, .. }
+            | ChainAction::Reveal { table_id, .. }
+            | ChainAction::Done { table_id } => *table_id,
+        }
+    }
+}
+
+/// What players commit to: SHA-256 of the bid (16 bytes, little endian) followed by a secret salt.
+pub fn bid_commitment(bid: Amount, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(bid.to_le_bytes());
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+impl Auction {
+    /// Bids can be revealed once everyone committed, or the commit window closed.
+    pub fn reveals_open(&self, timestamp: u128) -> bool {
+        timestamp > self.commit_deadline || self.bidders.values().all(|b| b.commitment.is_some())
+    }
+
+    /// Whether the auction can be settled: every committed bid is revealed, or it is too late to.
+    pub fn is_over(&self, timestamp: u128) -> bool {
+        timestamp > self.reveal_deadline
+            || (self.reveals_open(timestamp)
+                && self
+                    .bidders
+                    .values()
+                    .all(|b| b.commitment.is_none() || b.bid.is_some()))
+    }
+
+    /// The highest revealed bid, ties going to the first player by identity.
+    pub fn winner(&self) -> Option<(&Identity, Amount)> {
+        let mut winner: Option<(&Identity, Amount)> = None;
+        for (id, bidder) in &self.bidders {
+            let Some(bid) = bidder.bid else {
+                continue;
+            };
+            match winner {
+                Some((_, best)) if best >= bid => {}
+                _ => winner = Some((id, bid)),
+            }
+        }
+        winner
+    }
+
+    /// Every ante goes into the pot. The winner takes it and pays their bid, which is burned.
+    /// Without any revealed bid, antes are returned.
+    pub fn final_results(&self) -> Result<Vec<(Identity, AmountDelta)>> {
+        let pot = self
+            .bidders
+            .values()
+            .try_fold(0, |pot, b| amount::checked_add(pot, b.ante))?;
+        let winner = self.winner();
+        let mut results = Vec::new();
+        for (id, bidder) in &self.bidders {
+            let delta = match winner {
+                None => 0,
+                Some((winner_id, bid)) if winner_id == id => {
+                    amount::delta_between(amount::checked_add(bid, bidder.ante)?, pot)?
+                }
+                Some(_) => amount::delta_between(bidder.ante, 0)?,
+            };
+            results.push((id.clone(), delta));
+        }
+        Ok(results)
+    }
+}
+
+impl GameState {
+    pub fn new(board_contract: ContractName, backend_identity: Identity) -> Self {
+        Self {
+            auctions: BTreeMap::new(),
+            board_contract,
+            backend_identity,
+            last_interaction_time: 0,
+            lane_id: LaneId::default(),
+        }
+    }
+
+    pub fn process_chain_action(
+        &mut self,
+        identity: &Identity,
+        action: &ChainAction,
+        timestamp: u128,
+        ctx: Option<(&ChainActionBlob, &mut ExecutionContext)>,
+    ) -> Result<Vec<ChainEvent>> {
+        let mut events = Vec::new();
+
+        match action {
+            ChainAction::InitMinigame { table_id, players } => {
+                if self.auctions.contains_key(table_id) {
+                    return Err(anyhow!(
+                        "An auction is already running at table {}",
+                        table_id
+                    ));
+                }
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // Check our data matches the board contract
+                    minigame_interface::verify_board_start::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        players,
+                        None,
+                    )?;
+                }
+
+                let bidders = players
+                    .iter()
+                    .map(|(id, name, ante, coins)| {
+                        (
+                            id.clone(),
+                            Bidder {
+                                id: id.clone(),
+                                name: name.clone(),
+                                ante: *ante,
+                                coins: *coins,
+                                commitment: None,
+                                bid: None,
+                            },
+                        )
+                    })
+                    .collect();
+                let commit_deadline = timestamp.saturating_add(COMMIT_WINDOW_MS);
+                self.auctions.insert(
+                    *table_id,
+                    Auction {
+                        bidders,
+                        commit_deadline,
+                        reveal_deadline: commit_deadline.saturating_add(REVEAL_WINDOW_MS),
+                    },
+                );
+
+                events.push(ChainEvent::MinigameInitialized {
+                    table_id: *table_id,
+                    player_count: players.len(),
+                });
+            }
+
+            ChainAction::Commit {
+                table_id,
+                player_id,
+                commitment,
+            } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+                let auction = self.get_auction_mut(*table_id)?;
+                if auction.reveals_open(timestamp) {
+                    return Err(anyhow!("Bids can no longer be committed"));
+                }
+                let Some(bidder) = auction.bidders.get_mut(player_id) else {
+                    return Err(anyhow!("Player not found"));
+                };
+                // Players may change their mind until the commit window closes.
+                bidder.commitment = Some(*commitment);
+
+                events.push(ChainEvent::BidCommitted {
+                    table_id: *table_id,
+                    player_id: player_id.clone(),
+                });
+            }
+
+            ChainAction::Reveal {
+                table_id,
+                player_id,
+                bid,
+                salt,
+            } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+                let auction = self.get_auction_mut(*table_id)?;
+                if !auction.reveals_open(timestamp) {
+                    return Err(anyhow!("Bids cannot be revealed yet"));
+                }
+                if timestamp > auction.reveal_deadline {
+                    return Err(anyhow!("Bids can no longer be revealed"));
+                }
+                let Some(bidder) = auction.bidders.get_mut(player_id) else {
+                    return Err(anyhow!("Player not found"));
+                };
+                if bidder.bid.is_some() {
+                    return Err(anyhow!("Bid already revealed"));
+                }
+                if bidder.commitment != Some(bid_commitment(*bid, salt)) {
+                    return Err(anyhow!("Bid does not match the commitment"));
+                }
+                if *bid > bidder.coins {
+                    return Err(anyhow!(
+                        "Bid of {} is more than the {} coins available",
+                        bid,
+                        bidder.coins
+                    ));
+                }
+                bidder.bid = Some(*bid);
+
+                events.push(ChainEvent::BidRevealed {
+                    table_id: *table_id,
+                    player_id: player_id.clone(),
+                    bid: *bid,
+                });
+            }
+
+            ChainAction::Done { table_id } => {
+                let auction = self.get_auction_mut(*table_id)?;
+                if !auction.is_over(timestamp) {
+                    return Err(anyhow!("Cannot end the auction while bids are pending"));
+                }
+                let expected_final_results = auction.final_results()?;
+                let pot = auction.bidders.values().map(|b| b.ante).sum();
+                let winner = auction.winner().map(|(id, bid)| (id.clone(), bid));
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // When ending the minigame, verify that the board game is being updated with the correct data
+                    minigame_interface::verify_board_end::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        &expected_final_results,
+                    )?;
+                }
+
+                self.auctions.remove(table_id);
+                events.push(ChainEvent::AuctionSettled {
+                    table_id: *table_id,
+                    winning_bid: winner.as_ref().map(|(_, bid)| *bid).unwrap_or_default(),
+                    winner: winner.map(|(id, _)| id),
+                    pot,
+                });
+                events.push(ChainEvent::MinigameEnded {
+                    table_id: *table_id,
+                    final_results: expected_final_results,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn get_auction_mut(&mut self, table_id: TableId) -> Result<&mut Auction> {
+        self.auctions
+            .get_mut(&table_id)
+            .ok_or_else(|| anyhow!("No auction running at table {}", table_id))
+    }
+}
@@ -0,0 +1,65 @@
+use core::fmt;
+
+use crate::ChainEvent;
+
+impl fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEvent::MinigameInitialized {
+                table_id,
+                player_count,
+            } => {
+                write!(
+                    f,
+                    "Auction initialized at table {} with {} players",
+                    table_id, player_count
+                )
+            }
+            ChainEvent::BidCommitted {
+                table_id,
+                player_id,
+            } => {
+                write!(
+                    f,
+                    "Player {} committed a bid at table {}",
+                    player_id, table_id
+                )
+            }
+            ChainEvent::BidRevealed {
+                table_id,
+                player_id,
+                bid,
+            } => {
+                write!(
+                    f,
+                    "Player {} revealed a bid of {} at table {}",
+                    player_id, bid, table_id
+                )
+            }
+            ChainEvent::AuctionSettled {
+                table_id,
+                winner,
+                winning_bid,
+                pot,
+            } => match winner {
+                Some(winner) => write!(
+                    f,
+                    "Player {} won the pot of {} with a bid of {} at table {}",
+                    winner, pot, winning_bid, table_id
+                ),
+                None => write!(f, "Nobody bid at table {}, antes are returned", table_id),
+            },
+            ChainEvent::MinigameEnded {
+                table_id,
+                final_results,
+            } => {
+                write!(
+                    f,
+                    "Auction ended at table {} with {} player results",
+                    table_id,
+                    final_results.len()
+                )
+            }
+        }
+    }
+}
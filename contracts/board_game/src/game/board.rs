@@ -0,0 +1,71 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+pub enum Tile {
+    Start,
+    /// Gain (or lose, if negative) some coins.
+    Coins(i32),
+    /// A minigame is played at the end of the round.
+    Minigame,
+    /// Swap positions with another random player.
+    Swap,
+    /// Jump straight to another tile.
+    Shortcut {
+        to: usize,
+    },
+}
+
+/// A looping board: moving past the last tile wraps around to the start.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Board {
+    pub tiles: Vec<Tile>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::orange_trail()
+    }
+}
+
+impl Board {
+    /// The Orange Trail map, from the landing site across the Martian plains.
+    pub fn orange_trail() -> Self {
+        Self {
+            tiles: vec![
+                Tile::Start,
+                Tile::Coins(3),
+                Tile::Coins(-2),
+                Tile::Minigame,
+                Tile::Coins(5),
+                Tile::Swap,
+                Tile::Coins(-3),
+                Tile::Shortcut { to: 14 },
+                Tile::Coins(2),
+                Tile::Minigame,
+                Tile::Coins(-5),
+                Tile::Coins(3),
+                Tile::Swap,
+                Tile::Coins(-2),
+                Tile::Coins(4),
+                Tile::Minigame,
+                Tile::Coins(-4),
+                Tile::Coins(6),
+                Tile::Shortcut { to: 3 },
+                Tile::Coins(2),
+                Tile::Swap,
+                Tile::Coins(-3),
+                Tile::Minigame,
+                Tile::Coins(10),
+            ],
+        }
+    }
+
+    pub fn tile(&self, position: usize) -> &Tile {
+        &self.tiles[position % self.tiles.len()]
+    }
+
+    pub fn advance(&self, position: usize, steps: u8) -> usize {
+        (position + steps as usize) % self.tiles.len()
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use sdk::ContractName;
+use serde::{Deserialize, Serialize};
+
+use super::amount::Amount;
+use super::payout::{self, PayoutPolicy};
+use super::wheel::{self, WheelSegment};
+
+/// Hard limit on the number of players at a table, whatever the configuration.
+pub const MAX_PLAYERS: usize = 20;
+/// Hard limit on a single deposit, whatever the configuration.
+pub const MAX_DEPOSIT: Amount = 10_000_000;
+
+/// Rules of a single game, chosen when the table is initialized.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct GameConfig {
+    pub rounds: usize,
+    pub max_players: usize,
+    pub registration_window_ms: u64,
+    pub betting_window_ms: u64,
+    pub turn_timeout_ms: u64,
+    /// Coins lost by players who don't bet during a round (after the first one).
+    pub no_bet_penalty: Amount,
+    pub max_deposit: Amount,
+    /// Relative odds of each minigame, in the order they were given at Initialize.
+    /// Missing weights default to 1.
+    pub minigame_weights: Vec<u32>,
+    /// Always played as the last round, instead of a random minigame.
+    pub final_minigame: Option<ContractName>,
+    /// Never draw the same minigame twice in a row, when there is a choice.
+    pub no_repeat: bool,
+    /// Segments of the prize wheel spun at the end of each betting phase.
+    pub wheel: Vec<WheelSegment>,
+    pub payout: PayoutPolicy,
+    /// Share of the pot kept by the house, in basis points. It is always paid to the
+    /// board's backend identity, never to an account picked by the table creator.
+    pub house_fee_bps: u16,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            rounds: 10,
+            max_players: MAX_PLAYERS,
+            registration_window_ms: 55_000,
+            betting_window_ms: 30_000,
+            turn_timeout_ms: 15_000,
+            no_bet_penalty: 10,
+            max_deposit: MAX_DEPOSIT,
+            minigame_weights: Vec::new(),
+            final_minigame: None,
+            no_repeat: false,
+            wheel: wheel::default_wheel(),
+            payout: PayoutPolicy::default(),
+            house_fee_bps: 0,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=50).contains(&self.rounds) {
+            return Err(anyhow!("Rounds must be between 1 and 50"));
+        }
+        if !(2..=MAX_PLAYERS).contains(&self.max_players) {
+            return Err(anyhow!("Max players must be between 2 and {}", MAX_PLAYERS));
+        }
+        if !(5_000..=600_000).contains(&self.registration_window_ms) {
+            return Err(anyhow!(
+                "Registration window must be between 5 seconds and 10 minutes"
+            ));
+        }
+        if !(5_000..=120_000).contains(&self.betting_window_ms) {
+            return Err(anyhow!(
+                "Betting window must be between 5 seconds and 2 minutes"
+            ));
+        }
+        if !(5_000..=120_000).contains(&self.turn_timeout_ms) {
+            return Err(anyhow!(
+                "Turn timeout must be between 5 seconds and 2 minutes"
+            ));
+        }
+        if !(1..=MAX_DEPOSIT).contains(&self.max_deposit) {
+            return Err(anyhow!("Max deposit must be between 1 and {}", MAX_DEPOSIT));
+        }
+        if self.no_bet_penalty > self.max_deposit {
+            return Err(anyhow!("No-bet penalty cannot exceed the max deposit"));
+        }
+        wheel::validate(&self.wheel, self.max_deposit)?;
+        payout::validate(&self.payout, self.house_fee_bps, self.max_players)?;
+        Ok(())
+    }
+
+    pub fn validate_minigames(&self, minigames: &[ContractName]) -> Result<()> {
+        if self.minigame_weights.len() > minigames.len() {
+            return Err(anyhow!("More minigame weights than minigames"));
+        }
+        let total_weight = minigames
+            .iter()
+            .enumerate()
+            .map(|(i, _)| self.minigame_weights.get(i).copied().unwrap_or(1) as u64)
+            .sum::<u64>();
+        if total_weight == 0 {
+            return Err(anyhow!("At least one minigame must have a non-zero weight"));
+        }
+        if let Some(final_minigame) = &self.final_minigame {
+            if !minigames.contains(final_minigame) {
+                return Err(anyhow!(
+                    "Final minigame {} is not part of the game",
+                    final_minigame
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,30 +1,69 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
+/// xoshiro256** generator, seeded from 32 bytes.
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct Dice {
     min: u8,
     max: u8,
-    pub seed: u64,
+    state: [u64; 4],
 }
 
 impl Default for Dice {
     fn default() -> Self {
-        Self::new(1, 10, 12345)
+        Self::new(1, 10, [0; 32])
     }
 }
 
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
 impl Dice {
-    pub fn new(min: u8, max: u8, seed: u64) -> Self {
+    pub fn new(min: u8, max: u8, seed: [u8; 32]) -> Self {
         assert!(min < max, "Minimum value must be less than maximum value");
-        Self { min, max, seed }
+        let mut state = [0u64; 4];
+        for (i, word) in state.iter_mut().enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&seed[i * 8..(i + 1) * 8]);
+            // Spread the seed so that the state is never all zeroes.
+            *word = splitmix64(u64::from_le_bytes(bytes) ^ i as u64);
+        }
+        Self { min, max, state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
     }
 
     pub fn roll(&mut self) -> u8 {
-        // Simple Linear Congruential Generator
-        self.seed = self.seed.wrapping_mul(1103515245).wrapping_add(12345);
         let range = (self.max - self.min + 1) as u64;
-        self.min + ((self.seed >> 16) % range) as u8
+        self.min + self.range(range) as u8
+    }
+
+    /// Returns a value in `0..upper`, without modulo bias.
+    pub fn range(&mut self, upper: u64) -> u64 {
+        assert!(upper > 0, "Range must not be empty");
+        // Reject the incomplete last block of values so that every result is equally likely.
+        let zone = u64::MAX - (u64::MAX % upper);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % upper;
+            }
+        }
     }
 
     pub fn shuffle<T>(&mut self, slice: &mut [T]) {
@@ -34,8 +73,87 @@ impl Dice {
         }
         for i in (1..len).rev() {
             // Use dice's RNG to pick a random index
-            let j = (self.roll() as usize) % (i + 1);
+            let j = self.range(i as u64 + 1) as usize;
             slice.swap(i, j);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_seed_gives_fixed_sequence() {
+        let mut dice = Dice::new(1, 10, [0; 32]);
+        assert_eq!(
+            [dice.next_u64(), dice.next_u64(), dice.next_u64()],
+            [0x65094a0ab526fa3a, 0x14790c77058b9213, 0x545e14c068d2cdfc]
+        );
+
+        let mut dice = Dice::new(1, 10, [0; 32]);
+        let rolls: Vec<u8> = (0..10).map(|_| dice.roll()).collect();
+        assert_eq!(rolls, [7, 2, 7, 5, 3, 8, 4, 4, 2, 10]);
+
+        let seed: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let mut dice = Dice::new(1, 6, seed);
+        let rolls: Vec<u8> = (0..10).map(|_| dice.roll()).collect();
+        assert_eq!(rolls, [4, 5, 1, 4, 2, 2, 5, 6, 2, 3]);
+    }
+
+    #[test]
+    fn seeds_give_different_sequences() {
+        let mut a = Dice::new(1, 10, [0; 32]);
+        let mut b = Dice::new(1, 10, [1; 32]);
+        let a: Vec<u64> = (0..4).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..4).map(|_| b.next_u64()).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn range_stays_below_its_bound() {
+        let mut dice = Dice::default();
+        for upper in [1, 2, 3, 6, 7, 10, 100, 1 << 63, u64::MAX] {
+            for _ in 0..1_000 {
+                assert!(dice.range(upper) < upper);
+            }
+        }
+    }
+
+    #[test]
+    fn range_hits_every_value() {
+        let mut dice = Dice::default();
+        let mut counts = [0u32; 6];
+        for _ in 0..6_000 {
+            counts[dice.range(6) as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|c| (800..=1_200).contains(c)),
+            "{:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn rolls_stay_within_the_faces() {
+        let mut dice = Dice::new(3, 8, [42; 32]);
+        for _ in 0..1_000 {
+            assert!((3..=8).contains(&dice.roll()));
+        }
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation() {
+        let mut dice = Dice::default();
+        let mut values: Vec<u32> = (0..10).collect();
+        dice.shuffle(&mut values);
+        assert_eq!(values, [7, 3, 0, 1, 5, 9, 8, 4, 2, 6]);
+
+        for len in 0..20 {
+            let mut values: Vec<u32> = (0..len).collect();
+            dice.shuffle(&mut values);
+            values.sort();
+            assert_eq!(values, (0..len).collect::<Vec<_>>());
+        }
+    }
+}
@@ -2,31 +2,71 @@ use anyhow::{anyhow, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
 use sdk::{ContractName, Identity, LaneId, StateCommitment};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 
+pub mod board;
+pub mod config;
 pub mod dice;
+pub mod payout;
 pub mod player;
 pub mod utils;
+pub mod wheel;
 
-const ROUNDS: usize = 10;
-const MAX_PLAYERS: usize = 20;
+pub use config::GameConfig;
+pub use minigame_interface::{
+    amount, Amount, AmountDelta, MinigameResult, MinigameSetup, PlayerMinigameResult, TableId,
+};
+
+const MAX_TABLES: usize = 32;
+/// How many of a player's most recent action UUIDs are remembered to reject replays.
+const MAX_USED_UUIDS: usize = 64;
+/// How long the backend has to reveal the dice seed once the game started. Past this,
+/// players may seed the dice from their entropy alone.
+const SEED_REVEAL_WINDOW_MS: u128 = 60_000;
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct GameState {
+    pub tables: BTreeMap<TableId, Table>,
+
+    // Metadata to ensure the game runs smoothly
+    pub backend_identity: Identity,
+    pub lane_id: LaneId,
+}
+
+/// A single, independent game. Each table has its own phase, players, dice and bets.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Table {
     pub players: Vec<Player>,
-    pub max_players: usize,
+    pub config: GameConfig,
     pub minigames: Vec<ContractName>,
     pub dice: dice::Dice,
+    // The backend commits to a seed at Initialize and reveals it once registration is over,
+    // so that neither the backend nor the players can predict the dice on their own.
+    pub seed_commitment: [u8; 32],
+    // UUID of the Initialize action, from which the backend derives its seed.
+    pub seed_nonce: u128,
+    pub entropy: [u8; 32],
+    pub seed_revealed: bool,
+    // Past this, the wheel may be spun with a seed from the players' entropy alone.
+    pub reveal_deadline: u128,
     pub phase: GamePhase,
     pub round_started_at: u128,
+    // Once passed, any player may AdvancePhase instead of waiting for the backend.
+    pub deadline: u128,
     pub round: usize,
-    pub bets: BTreeMap<Identity, u64>,
+    pub bets: BTreeMap<Identity, Amount>,
     pub all_or_nothing: bool,
 
-    // Metadata to ensure the game runs smoothly
-    pub backend_identity: Identity,
+    pub board: board::Board,
+    // Index in `players` of the player whose turn it is during BoardMoves.
+    pub turn: usize,
+    pub has_rolled: bool,
+    // Set by the wheel or a minigame tile, played once every player has moved.
+    pub pending_minigame: bool,
+    pub last_minigame: Option<ContractName>,
+
     pub last_interaction_time: u128,
-    pub lane_id: LaneId,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -34,27 +74,19 @@ pub struct Player {
     pub id: Identity,
     pub name: String,
     pub position: usize,
-    pub coins: i32,
+    pub coins: Amount,
+    // The oranj deposited at registration, refunded if the game is abandoned.
+    pub deposit: Amount,
+    // Sliding window of the last MAX_USED_UUIDS actions sent by this player.
     pub used_uuids: Vec<u128>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
-pub struct MinigameResult {
-    pub contract_name: ContractName,
-    pub player_results: Vec<PlayerMinigameResult>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
-pub struct PlayerMinigameResult {
-    pub player_id: Identity,
-    pub coins_delta: i32,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 pub enum GamePhase {
     Registration,
     Betting,
     WheelSpin,
+    BoardMoves,
     StartMinigame(ContractName),
     InMinigame(ContractName),
     FinalMinigame(ContractName),
@@ -62,33 +94,47 @@ pub enum GamePhase {
     GameOver,
 }
 
-pub type MinigameSetup = Vec<(Identity, String, u64)>;
-
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
 pub enum GameAction {
     EndGame,
     Initialize {
         minigames: Vec<String>,
-        random_seed: u64,
+        /// SHA-256 of the seed revealed with RevealSeed.
+        #[serde(default)]
+        seed_commitment: [u8; 32],
+        #[serde(default)]
+        config: GameConfig,
     },
     RegisterPlayer {
         name: String,
-        deposit: u64, // Initial deposit in coins
+        deposit: Amount, // Initial deposit in coins
+        /// Optional randomness contributed by the player, mixed into the dice seed.
+        #[serde(default)]
+        entropy: Option<u64>,
+    },
+    RevealSeed {
+        seed: [u8; 32],
     },
     StartGame,
     PlaceBet {
-        amount: u64,
+        amount: Amount,
     },
     SpinWheel,
     StartMinigame {
         minigame: ContractName,
         players: MinigameSetup,
+        /// Set for minigames seeded from the table's dice, which must match `minigame_seed`.
+        #[serde(default)]
+        seed: Option<[u8; 32]>,
     },
     EndMinigame {
         result: MinigameResult,
     },
+    RollDice,
     EndTurn,
     DistributeRewards,
+    LeaveGame,
+    AdvancePhase,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -103,7 +149,7 @@ pub enum GameEvent {
     },
     CoinsChanged {
         player_id: Identity,
-        amount: i32,
+        amount: AmountDelta,
     },
     MinigameReady {
         minigame_type: String,
@@ -119,10 +165,13 @@ pub enum GameEvent {
     },
     GameEnded {
         winner_id: Identity,
-        final_coins: i32,
+        final_coins: Amount,
     },
     GameInitialized {
-        random_seed: u64,
+        seed_commitment: [u8; 32],
+    },
+    SeedRevealed {
+        seed: [u8; 32],
     },
     PlayerRegistered {
         name: String,
@@ -133,16 +182,40 @@ pub enum GameEvent {
     },
     BetPlaced {
         player_id: Identity,
-        amount: u64,
+        amount: Amount,
     },
     WheelSpun {
         round: usize, // for convenience on frontend
-        outcome: u8,
+        outcome: u8,  // index of the segment in the wheel
+        effect: wheel::WheelEffect,
     },
     PlayersSwappedCoins {
         swaps: Vec<(Identity, Identity)>,
     },
     AllOrNothingActivated,
+    TaxCollected {
+        percent: u8,
+    },
+    BonusAwarded {
+        amount: Amount,
+    },
+    BetsDoubled,
+    RoundSkipped {
+        round: usize,
+    },
+    PlayerLeft {
+        player_id: Identity,
+        refund: Amount,
+    },
+    PhaseAdvanced {
+        player_id: Identity,
+    },
+    SeedRevealTimedOut,
+    LandedOnTile {
+        player_id: Identity,
+        position: usize,
+        tile: board::Tile,
+    },
 }
 
 impl From<StateCommitment> for GameState {
@@ -153,55 +226,157 @@ impl From<StateCommitment> for GameState {
 
 impl GameState {
     pub fn new(backend_identity: Identity) -> Self {
+        Self {
+            tables: BTreeMap::new(),
+            backend_identity,
+            lane_id: LaneId::default(),
+        }
+    }
+
+    pub fn table(&self, table_id: TableId) -> Result<&Table> {
+        self.tables
+            .get(&table_id)
+            .ok_or_else(|| anyhow!("Table {} does not exist", table_id))
+    }
+
+    pub fn process_action(
+        &mut self,
+        caller: &Identity,
+        uuid: u128,
+        table_id: TableId,
+        action: GameAction,
+        timestamp: u128,
+    ) -> Result<Vec<GameEvent>> {
+        // Tables are created on demand by Initialize, and dropped once the game has ended.
+        let is_new_table = !self.tables.contains_key(&table_id);
+        if is_new_table {
+            if !matches!(action, GameAction::Initialize { .. }) {
+                return Err(anyhow!("Table {} does not exist", table_id));
+            }
+            if self.tables.len() >= MAX_TABLES {
+                return Err(anyhow!("Too many tables are already open"));
+            }
+            self.tables.insert(table_id, Table::new());
+        }
+        let is_end_game = matches!(action, GameAction::EndGame);
+        let backend_identity = self.backend_identity.clone();
+        let table = self
+            .tables
+            .get_mut(&table_id)
+            .ok_or_else(|| anyhow!("Table {} does not exist", table_id))?;
+
+        let result = table
+            .check_uuid(caller, uuid)
+            .and_then(|_| table.process_action(caller, &backend_identity, uuid, action, timestamp));
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                if is_new_table {
+                    self.tables.remove(&table_id);
+                }
+                return Err(e);
+            }
+        };
+
+        if is_end_game {
+            self.tables.remove(&table_id);
+        } else {
+            table.record_uuid(caller, uuid);
+            table.last_interaction_time = timestamp;
+        }
+
+        Ok(events)
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
         Self {
             players: Vec::new(),
             phase: GamePhase::GameOver,
-            max_players: MAX_PLAYERS,
+            config: GameConfig::default(),
             minigames: Vec::new(),
-            dice: dice::Dice::new(1, 10, 0),
+            dice: dice::Dice::default(),
+            seed_commitment: [0; 32],
+            seed_nonce: 0,
+            entropy: [0; 32],
+            seed_revealed: false,
+            reveal_deadline: 0,
             round_started_at: 0,
+            deadline: 0,
             round: 0,
             bets: BTreeMap::new(),
             all_or_nothing: false,
 
-            backend_identity,
+            board: board::Board::orange_trail(),
+            turn: 0,
+            has_rolled: false,
+            pending_minigame: false,
+            last_minigame: None,
+
             last_interaction_time: 0,
-            lane_id: LaneId::default(),
         }
     }
 
-    pub fn reset(&mut self, minigames: Vec<ContractName>, random_seed: u64) {
+    pub fn reset(
+        &mut self,
+        config: GameConfig,
+        minigames: Vec<ContractName>,
+        seed_commitment: [u8; 32],
+    ) {
         *self = Self {
-            players: Vec::with_capacity(MAX_PLAYERS),
+            players: Vec::with_capacity(config.max_players),
             phase: GamePhase::GameOver,
-            max_players: MAX_PLAYERS,
+            config,
             minigames,
-            dice: dice::Dice::new(1, 10, random_seed),
+            dice: dice::Dice::default(),
+            seed_commitment,
+            seed_nonce: self.seed_nonce,
+            entropy: [0; 32],
+            seed_revealed: false,
+            reveal_deadline: 0,
             round_started_at: 0,
+            deadline: 0,
             round: 0,
             bets: BTreeMap::new(),
             all_or_nothing: false,
 
-            backend_identity: self.backend_identity.clone(),
+            board: board::Board::orange_trail(),
+            turn: 0,
+            has_rolled: false,
+            pending_minigame: false,
+            last_minigame: None,
+
             last_interaction_time: self.last_interaction_time,
-            lane_id: self.lane_id.clone(),
         }
     }
 
-    // Helper function for updating coins and generating events
+    // Helper function for updating coins and generating events.
+    // Players never lose more than what they have, the event reports the actual change.
     fn update_player_coins(
         &mut self,
         player_index: usize,
-        delta: i32,
+        delta: AmountDelta,
         events: &mut Vec<GameEvent>,
     ) -> Result<()> {
         let Some(player) = self.players.get_mut(player_index) else {
             return Err(anyhow!("Player not found"));
         };
-        player.coins = (player.coins + delta).max(0);
+        let before = player.coins;
+        player.coins = if delta >= 0 {
+            amount::checked_add(before, delta.unsigned_abs())?
+        } else {
+            before - delta.unsigned_abs().min(before)
+        };
         events.push(GameEvent::CoinsChanged {
             player_id: player.id.clone(),
-            amount: delta,
+            amount: amount::delta_between(before, player.coins)?,
         });
         Ok(())
     }
@@ -213,11 +388,21 @@ impl GameState {
                 self.players
                     .iter()
                     .find(|p| p.id == *id && p.coins > 0)
-                    .map(|p| (p.id.clone(), p.name.clone(), bet))
+                    .map(|p| (p.id.clone(), p.name.clone(), bet, p.coins))
             })
             .collect()
     }
 
+    /// Randomness for minigames, drawn from a copy of the dice so that the board's own rolls are unaffected.
+    pub fn minigame_seed(&self) -> [u8; 32] {
+        let mut dice = self.dice.clone();
+        let mut seed = [0u8; 32];
+        for chunk in seed.chunks_mut(8) {
+            chunk.copy_from_slice(&dice.next_u64().to_le_bytes());
+        }
+        seed
+    }
+
     // Helper function for handling minigame results
     fn apply_minigame_result(
         &mut self,
@@ -231,6 +416,68 @@ impl GameState {
         Ok(())
     }
 
+    /// Folds a player's contribution into the table entropy. Players register before the
+    /// seed is revealed, so they can't tailor their entropy to it.
+    fn mix_entropy(&mut self, player_id: &Identity, entropy: u64) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.entropy);
+        hasher.update(player_id.0.as_bytes());
+        hasher.update(entropy.to_le_bytes());
+        self.entropy = hasher.finalize().into();
+    }
+
+    /// Without a reveal from the backend, the dice are seeded from the players' entropy alone.
+    fn seed_from_entropy(&mut self) {
+        self.dice = dice::Dice::new(1, 10, Sha256::digest(self.entropy).into());
+        self.seed_revealed = true;
+    }
+
+    fn ensure_seed_revealed(&self) -> Result<()> {
+        if !self.seed_revealed {
+            return Err(anyhow!("The dice seed has not been revealed yet"));
+        }
+        Ok(())
+    }
+
+    /// The `oxygen` transfers owed to the players (and treasury) once the game is over.
+    pub fn reward_transfers(&self, treasury: &Identity) -> Result<Vec<(Identity, Amount)>> {
+        payout::reward_transfers(&self.players, &self.config, treasury)
+    }
+
+    /// Rejects an action whose UUID the player already used recently.
+    fn check_uuid(&self, caller: &Identity, uuid: u128) -> Result<()> {
+        let replayed = self
+            .players
+            .iter()
+            .any(|p| p.id == *caller && p.used_uuids.contains(&uuid));
+        if replayed {
+            return Err(anyhow!("Action {} was already sent by {}", uuid, caller));
+        }
+        Ok(())
+    }
+
+    fn record_uuid(&mut self, caller: &Identity, uuid: u128) {
+        if let Some(player) = self.players.iter_mut().find(|p| p.id == *caller) {
+            if player.used_uuids.len() >= MAX_USED_UUIDS {
+                player.used_uuids.remove(0);
+            }
+            player.used_uuids.push(uuid);
+        }
+    }
+
+    /// Deposits owed to players if the game is ended before it reached its end.
+    /// Players who were knocked out or forfeited get nothing back.
+    pub fn refunds(&self) -> Vec<(Identity, Amount)> {
+        if self.phase == GamePhase::GameOver {
+            return Vec::new();
+        }
+        self.players
+            .iter()
+            .filter(|p| p.coins > 0 && p.deposit > 0)
+            .map(|p| (p.id.clone(), p.deposit))
+            .collect()
+    }
+
     fn is_registered(&self, caller: &Identity) -> bool {
         self.players.iter().any(|p| p.id == *caller && p.coins > 0)
     }
@@ -259,10 +506,318 @@ impl GameState {
         }
     }
 
+    fn start_timed_phase(&mut self, phase: GamePhase, timestamp: u128, window_ms: u64) {
+        self.round_started_at = timestamp;
+        self.deadline = timestamp.saturating_add(window_ms as u128);
+        self.phase = phase;
+    }
+
+    fn start_next_round(&mut self, timestamp: u128) {
+        self.round += 1;
+        self.bets.clear();
+        self.start_timed_phase(GamePhase::Betting, timestamp, self.config.betting_window_ms);
+    }
+
+    /// Moves on from the betting phase once every player still in the game has bet.
+    fn close_betting_if_done(&mut self, events: &mut Vec<GameEvent>) -> Result<()> {
+        // Only require bets from players with coins > 0
+        let active_players = self.players.iter().filter(|p| p.coins > 0).count();
+        if self.bets.len() != active_players {
+            return Ok(());
+        }
+        if self.round >= self.config.rounds - 1 {
+            let final_minigame = match self.config.final_minigame.clone() {
+                Some(final_minigame) => {
+                    self.last_minigame = Some(final_minigame.clone());
+                    final_minigame
+                }
+                None => self.draw_minigame()?,
+            };
+            events.push(GameEvent::MinigameReady {
+                minigame_type: final_minigame.0.clone(),
+            });
+            self.phase = GamePhase::FinalMinigame(final_minigame);
+        } else {
+            self.phase = GamePhase::WheelSpin;
+        }
+        Ok(())
+    }
+
+    fn next_active_player(&self, from: usize) -> Option<usize> {
+        (from..self.players.len()).find(|&i| self.players[i].coins > 0)
+    }
+
+    /// Every player still in the game takes a turn on the board, in registration order.
+    fn start_board_moves(&mut self, timestamp: u128, events: &mut Vec<GameEvent>) -> Result<()> {
+        let Some(first) = self.next_active_player(0) else {
+            return self.finish_board_moves(timestamp, events);
+        };
+        self.turn = first;
+        self.has_rolled = false;
+        self.start_timed_phase(
+            GamePhase::BoardMoves,
+            timestamp,
+            self.config.turn_timeout_ms,
+        );
+        Ok(())
+    }
+
+    fn next_turn(&mut self, timestamp: u128, events: &mut Vec<GameEvent>) -> Result<()> {
+        // A tile may have knocked players out of the game.
+        if self.check_and_handle_game_over(events) {
+            return Ok(());
+        }
+        match self.next_active_player(self.turn + 1) {
+            Some(next) => {
+                self.turn = next;
+                self.has_rolled = false;
+                self.round_started_at = timestamp;
+                self.deadline = timestamp.saturating_add(self.config.turn_timeout_ms as u128);
+                events.push(GameEvent::TurnEnded {
+                    next_player: self.players[next].id.clone(),
+                });
+                Ok(())
+            }
+            None => self.finish_board_moves(timestamp, events),
+        }
+    }
+
+    fn finish_board_moves(&mut self, timestamp: u128, events: &mut Vec<GameEvent>) -> Result<()> {
+        if self.check_and_handle_game_over(events) {
+            return Ok(());
+        }
+        // The minigame only makes sense if someone still has a bet on the table.
+        if std::mem::take(&mut self.pending_minigame) && !self.get_minigame_setup().is_empty() {
+            let minigame_type = self.draw_minigame()?;
+            events.push(GameEvent::MinigameReady {
+                minigame_type: minigame_type.0.clone(),
+            });
+            self.phase = GamePhase::StartMinigame(minigame_type);
+        } else {
+            self.start_next_round(timestamp);
+        }
+        Ok(())
+    }
+
+    /// Draws the next minigame from the dice, following the configured weights.
+    fn draw_minigame(&mut self) -> Result<ContractName> {
+        self.ensure_seed_revealed()?;
+        let weighted: Vec<(&ContractName, u64)> = self
+            .minigames
+            .iter()
+            .enumerate()
+            .map(|(i, minigame)| {
+                let weight = self.config.minigame_weights.get(i).copied().unwrap_or(1);
+                (minigame, weight as u64)
+            })
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        let mut candidates: Vec<_> = weighted
+            .iter()
+            .filter(|(minigame, _)| {
+                !self.config.no_repeat || self.last_minigame.as_ref() != Some(*minigame)
+            })
+            .cloned()
+            .collect();
+        // With a single playable minigame, repeating it is the only option.
+        if candidates.is_empty() {
+            candidates = weighted;
+        }
+
+        let total: u64 = candidates.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return Err(anyhow!("No minigame available"));
+        }
+        let mut draw = self.dice.range(total);
+        let mut drawn = None;
+        for (minigame, weight) in candidates {
+            if draw < weight {
+                drawn = Some(minigame.clone());
+                break;
+            }
+            draw -= weight;
+        }
+        let minigame = drawn.ok_or_else(|| anyhow!("No minigame available"))?;
+        self.last_minigame = Some(minigame.clone());
+        Ok(minigame)
+    }
+
+    /// Picks a wheel segment, following the configured weights.
+    fn spin_wheel(&mut self) -> Result<usize> {
+        let total: u64 = self.config.wheel.iter().map(|s| s.weight as u64).sum();
+        if total == 0 {
+            return Err(anyhow!("The wheel has no segments"));
+        }
+        let mut draw = self.dice.range(total);
+        for (i, segment) in self.config.wheel.iter().enumerate() {
+            if draw < segment.weight as u64 {
+                return Ok(i);
+            }
+            draw -= segment.weight as u64;
+        }
+        Err(anyhow!("The wheel has no segments"))
+    }
+
+    fn apply_wheel_effect(
+        &mut self,
+        effect: &wheel::WheelEffect,
+        timestamp: u128,
+        events: &mut Vec<GameEvent>,
+    ) -> Result<()> {
+        let active_players: Vec<_> = (0..self.players.len())
+            .filter(|&i| self.players[i].coins > 0)
+            .collect();
+        match effect {
+            wheel::WheelEffect::Nothing => {}
+            wheel::WheelEffect::RandomPayout => {
+                let bet_entries: Vec<_> = std::mem::take(&mut self.bets).into_iter().collect();
+                let mut player_indices = active_players;
+                self.dice.shuffle(&mut player_indices);
+                for (i, (bettor, amount)) in bet_entries.iter().enumerate() {
+                    // Remove bet from bettor
+                    let Some(bettor_idx) = self.players.iter().position(|p| p.id == *bettor) else {
+                        return Err(anyhow!("Bettor not found"));
+                    };
+                    self.update_player_coins(bettor_idx, -amount::to_delta(*amount)?, events)?;
+                    // Pay out to a random player
+                    let winner_idx = player_indices[i % player_indices.len()];
+                    self.update_player_coins(winner_idx, amount::to_delta(*amount)?, events)?;
+                }
+            }
+            wheel::WheelEffect::AllOrNothing => {
+                self.all_or_nothing = true;
+                events.push(GameEvent::AllOrNothingActivated);
+            }
+            wheel::WheelEffect::Minigame => {
+                self.pending_minigame = true;
+            }
+            wheel::WheelEffect::CoinSwap => {
+                let mut shuffled = active_players;
+                self.dice.shuffle(&mut shuffled);
+                // With an odd number of players, the last one keeps their coins.
+                let mut swaps = Vec::new();
+                for pair in shuffled.chunks_exact(2) {
+                    let (a, b) = (pair[0], pair[1]);
+                    let coins = self.players[a].coins;
+                    self.players[a].coins = self.players[b].coins;
+                    self.players[b].coins = coins;
+                    swaps.push((self.players[a].id.clone(), self.players[b].id.clone()));
+                }
+                events.push(GameEvent::PlayersSwappedCoins { swaps });
+            }
+            wheel::WheelEffect::Tax { percent } => {
+                for i in active_players {
+                    let tax = amount::checked_mul(self.players[i].coins, *percent as Amount)? / 100;
+                    if tax > 0 {
+                        self.update_player_coins(i, -amount::to_delta(tax)?, events)?;
+                    }
+                }
+                events.push(GameEvent::TaxCollected { percent: *percent });
+            }
+            wheel::WheelEffect::Bonus { amount } => {
+                for i in active_players {
+                    self.update_player_coins(i, amount::to_delta(*amount)?, events)?;
+                }
+                events.push(GameEvent::BonusAwarded { amount: *amount });
+            }
+            wheel::WheelEffect::DoubleBets => {
+                for (id, bet) in self.bets.iter_mut() {
+                    let coins = self
+                        .players
+                        .iter()
+                        .find(|p| p.id == *id)
+                        .map_or(0, |p| p.coins);
+                    *bet = amount::checked_mul(*bet, 2)?.min(coins);
+                }
+                self.pending_minigame = true;
+                events.push(GameEvent::BetsDoubled);
+            }
+            wheel::WheelEffect::SkipRound => {
+                self.pending_minigame = false;
+                events.push(GameEvent::RoundSkipped { round: self.round });
+                self.start_next_round(timestamp);
+                return Ok(());
+            }
+        }
+        self.start_board_moves(timestamp, events)
+    }
+
+    fn roll_and_move(&mut self, events: &mut Vec<GameEvent>) -> Result<()> {
+        if self.has_rolled {
+            return Err(anyhow!("Dice already rolled this turn"));
+        }
+        self.ensure_seed_revealed()?;
+        let index = self.turn;
+        let Some(player) = self.players.get(index) else {
+            return Err(anyhow!("Player not found"));
+        };
+        let player_id = player.id.clone();
+        let from = player.position;
+        let value = self.dice.roll();
+        let position = self.board.advance(from, value);
+        self.has_rolled = true;
+        events.push(GameEvent::DiceRolled {
+            player_id: player_id.clone(),
+            value,
+        });
+        self.move_player(index, position, events);
+
+        let tile = self.board.tile(position).clone();
+        events.push(GameEvent::LandedOnTile {
+            player_id,
+            position,
+            tile: tile.clone(),
+        });
+        self.apply_tile(index, &tile, events)
+    }
+
+    fn move_player(&mut self, index: usize, position: usize, events: &mut Vec<GameEvent>) {
+        let player = &mut self.players[index];
+        player.position = position;
+        events.push(GameEvent::PlayerMoved {
+            player_id: player.id.clone(),
+            new_position: position,
+        });
+    }
+
+    fn apply_tile(
+        &mut self,
+        index: usize,
+        tile: &board::Tile,
+        events: &mut Vec<GameEvent>,
+    ) -> Result<()> {
+        match tile {
+            board::Tile::Start => {}
+            board::Tile::Coins(delta) => {
+                self.update_player_coins(index, AmountDelta::from(*delta), events)?;
+            }
+            board::Tile::Minigame => {
+                self.pending_minigame = true;
+            }
+            board::Tile::Swap => {
+                let others: Vec<_> = (0..self.players.len())
+                    .filter(|&i| i != index && self.players[i].coins > 0)
+                    .collect();
+                if !others.is_empty() {
+                    let other = others[self.dice.range(others.len() as u64) as usize];
+                    let position = self.players[index].position;
+                    let other_position = self.players[other].position;
+                    self.move_player(index, other_position, events);
+                    self.move_player(other, position, events);
+                }
+            }
+            board::Tile::Shortcut { to } => {
+                self.move_player(index, to % self.board.tiles.len(), events);
+            }
+        }
+        Ok(())
+    }
+
     pub fn process_action(
         &mut self,
         caller: &Identity,
-        _uuid: u128,
+        backend_identity: &Identity,
+        uuid: u128,
         action: GameAction,
         timestamp: u128,
     ) -> Result<Vec<GameEvent>> {
@@ -270,7 +825,7 @@ impl GameState {
         match (self.phase.clone(), action) {
             (_, GameAction::EndGame) => {
                 let is_ended = self.phase == GamePhase::GameOver;
-                let is_backend = self.backend_identity == *caller;
+                let is_backend = *backend_identity == *caller;
                 let backend_timed_out = timestamp - self.last_interaction_time > 2 * 60 * 1000;
                 let game_timed_out = timestamp - self.last_interaction_time > 10 * 60 * 1000;
                 if is_ended || (is_backend && backend_timed_out) || game_timed_out {
@@ -278,7 +833,11 @@ impl GameState {
                         winner_id: Identity::default(),
                         final_coins: 0,
                     });
-                    self.reset(self.minigames.clone(), self.dice.seed);
+                    self.reset(
+                        self.config.clone(),
+                        self.minigames.clone(),
+                        self.seed_commitment,
+                    );
                 } else {
                     return Err(anyhow!("Only the backend can end the game"));
                 }
@@ -287,25 +846,40 @@ impl GameState {
                 GamePhase::GameOver,
                 GameAction::Initialize {
                     minigames,
-                    random_seed,
+                    seed_commitment,
+                    config,
                 },
             ) => {
                 if minigames.is_empty() {
                     return Err(anyhow!("Minigames cannot be empty"));
                 }
-                self.reset(
-                    minigames.into_iter().map(|x| x.into()).collect::<Vec<_>>(),
-                    random_seed,
-                );
+                let minigames = minigames
+                    .into_iter()
+                    .map(|x| x.into())
+                    .collect::<Vec<ContractName>>();
+                config.validate()?;
+                config.validate_minigames(&minigames)?;
+                self.reset(config, minigames, seed_commitment);
+                self.seed_nonce = uuid;
                 // Keep track of the time to know how long the registration phase lasts.
-                self.round_started_at = timestamp;
-                self.phase = GamePhase::Registration;
-                events.push(GameEvent::GameInitialized { random_seed });
+                self.start_timed_phase(
+                    GamePhase::Registration,
+                    timestamp,
+                    self.config.registration_window_ms,
+                );
+                events.push(GameEvent::GameInitialized { seed_commitment });
             }
 
             // Registration Phase
-            (GamePhase::Registration, GameAction::RegisterPlayer { name, deposit }) => {
-                if self.players.len() >= self.max_players {
+            (
+                GamePhase::Registration,
+                GameAction::RegisterPlayer {
+                    name,
+                    deposit,
+                    entropy,
+                },
+            ) => {
+                if self.players.len() >= self.config.max_players {
                     return Err(anyhow!("Game is full"));
                 }
 
@@ -323,7 +897,7 @@ impl GameState {
                 if deposit == 0 {
                     return Err(anyhow!("Deposit must be greater than zero"));
                 }
-                if deposit > 10000000 {
+                if deposit > self.config.max_deposit {
                     return Err(anyhow!("Deposit exceeds maximum allowed amount"));
                 }
 
@@ -331,9 +905,13 @@ impl GameState {
                     id: caller.clone(),
                     name: name.clone(),
                     position: 0,
-                    coins: deposit as i32,
+                    coins: deposit,
+                    deposit,
                     used_uuids: Vec::new(),
                 });
+                if let Some(entropy) = entropy {
+                    self.mix_entropy(caller, entropy);
+                }
 
                 events.push(GameEvent::PlayerRegistered {
                     name: name.clone(),
@@ -343,26 +921,55 @@ impl GameState {
 
             // Start Game Action
             (GamePhase::Registration, GameAction::StartGame) => {
-                let is_full = self.players.len() == self.max_players;
-                let registration_period_done =
-                    self.round_started_at.saturating_add(55 * 1000) < timestamp;
+                let is_full = self.players.len() == self.config.max_players;
+                let registration_period_done = self
+                    .round_started_at
+                    .saturating_add(self.config.registration_window_ms as u128)
+                    < timestamp;
                 if !is_full && !registration_period_done {
                     return Err(anyhow!(
                         "Game is not full and registration period is not over"
                     ));
                 }
 
-                self.phase = GamePhase::Betting;
-                self.round_started_at = timestamp;
+                self.start_timed_phase(
+                    GamePhase::Betting,
+                    timestamp,
+                    self.config.betting_window_ms,
+                );
+                self.reveal_deadline = timestamp.saturating_add(SEED_REVEAL_WINDOW_MS);
                 self.round = 0;
                 events.push(GameEvent::GameStarted {
                     player_count: self.players.len(),
                 });
             }
 
+            (phase, GameAction::RevealSeed { seed }) => {
+                // Revealing during registration would let late players grind their entropy.
+                if matches!(phase, GamePhase::Registration | GamePhase::GameOver) {
+                    return Err(anyhow!(
+                        "The seed can only be revealed once the game started"
+                    ));
+                }
+                if self.seed_revealed {
+                    return Err(anyhow!("The seed has already been revealed"));
+                }
+                if Sha256::digest(seed)[..] != self.seed_commitment[..] {
+                    return Err(anyhow!("Seed does not match the commitment"));
+                }
+                let mut hasher = Sha256::new();
+                hasher.update(seed);
+                hasher.update(self.entropy);
+                self.dice = dice::Dice::new(1, 10, hasher.finalize().into());
+                self.seed_revealed = true;
+                events.push(GameEvent::SeedRevealed { seed });
+            }
+
             // Betting Phase
             (GamePhase::Betting, GameAction::PlaceBet { amount }) => {
-                if timestamp.saturating_sub(self.round_started_at) > 30_000 {
+                if timestamp.saturating_sub(self.round_started_at)
+                    > self.config.betting_window_ms as u128
+                {
                     return Err(anyhow!("Betting time is over"));
                 }
                 if self.bets.contains_key(caller) {
@@ -376,10 +983,10 @@ impl GameState {
                     return Err(anyhow!("Player {} is out of the game (no coins)", caller));
                 }
                 if self.all_or_nothing {
-                    if amount != player.coins as u64 {
+                    if amount != player.coins {
                         return Err(anyhow!("All or nothing round: you must bet all your coins"));
                     }
-                } else if player.coins < amount as i32 {
+                } else if player.coins < amount {
                     return Err(anyhow!("Player {} does not have enough coins", caller));
                 }
                 self.bets.insert(caller.clone(), amount);
@@ -387,31 +994,18 @@ impl GameState {
                     player_id: caller.clone(),
                     amount,
                 });
-                // Only require bets from players with coins > 0
-                let active_players = self.players.iter().filter(|p| p.coins > 0).count();
-                if self.bets.len() == active_players {
-                    if self.round >= ROUNDS - 1 {
-                        let Some(final_minigame) = self.minigames.first() else {
-                            return Err(anyhow!("No final minigame available"));
-                        };
-                        events.push(GameEvent::MinigameReady {
-                            minigame_type: final_minigame.0.clone(),
-                        });
-                        self.phase = GamePhase::FinalMinigame(final_minigame.clone());
-                    } else {
-                        self.phase = GamePhase::WheelSpin;
-                    }
-                } else {
-                    self.phase = GamePhase::Betting;
-                }
+                self.close_betting_if_done(&mut events)?;
             }
 
             // Wheel Spin Phase
             (GamePhase::WheelSpin, GameAction::SpinWheel)
             | (GamePhase::Betting, GameAction::SpinWheel) => {
+                self.ensure_seed_revealed()?;
                 if self.phase == GamePhase::Betting {
                     // Check we're over the timeout
-                    if timestamp.saturating_sub(self.round_started_at) < 30_000 {
+                    if timestamp.saturating_sub(self.round_started_at)
+                        < self.config.betting_window_ms as u128
+                    {
                         return Err(anyhow!("Not enough time has passed"));
                     }
                     // Collect indices of players to penalize
@@ -429,8 +1023,9 @@ impl GameState {
                             // In round 0 or all_or_nothing, set coins to 0
                             self.players[i].coins = 0;
                         } else {
-                            // Otherwise, penalize 10 coins
-                            self.update_player_coins(i, -10, &mut events)?;
+                            // Otherwise, penalize a few coins
+                            let penalty = amount::to_delta(self.config.no_bet_penalty)?;
+                            self.update_player_coins(i, -penalty, &mut events)?;
                         }
                     }
                 }
@@ -441,71 +1036,47 @@ impl GameState {
                     return Ok(events);
                 }
                 // Use dice to determine the wheel outcome
-                let outcome = self.dice.roll() % 5;
+                let outcome = self.spin_wheel()?;
+                let effect = self.config.wheel[outcome].effect.clone();
                 events.push(GameEvent::WheelSpun {
-                    outcome,
+                    outcome: outcome as u8,
+                    effect: effect.clone(),
                     round: self.round,
                 });
-                match outcome {
-                    0 => {
-                        // Nothing happens, go to next round
-                        self.round += 1;
-                        self.bets.clear();
-                        self.round_started_at = timestamp;
-                        self.phase = GamePhase::Betting;
-                    }
-                    1 => {
-                        // Randomly pay out the bets to players
-                        let bet_entries: Vec<_> =
-                            std::mem::take(&mut self.bets).into_iter().collect();
-                        let mut player_indices: Vec<_> = (0..self.players.len())
-                            .filter(|&i| self.players[i].coins > 0)
-                            .collect();
-                        self.dice.shuffle(&mut player_indices);
-                        for (i, (bettor, amount)) in bet_entries.iter().enumerate() {
-                            // Remove bet from bettor
-                            let Some(bettor_idx) =
-                                self.players.iter().position(|p| p.id == *bettor)
-                            else {
-                                return Err(anyhow!("Bettor not found"));
-                            };
-                            self.update_player_coins(bettor_idx, -(*amount as i32), &mut events)?;
-                            // Pay out to a random player
-                            let winner_idx = player_indices[i % player_indices.len()];
-                            self.update_player_coins(winner_idx, *amount as i32, &mut events)?;
-                        }
-                        self.round += 1;
-                        self.bets.clear();
-                        self.round_started_at = timestamp;
-                        self.phase = GamePhase::Betting;
-                    }
-                    2 => {
-                        // All or nothing: players must bet all their coins next round
-                        self.all_or_nothing = true;
-                        events.push(GameEvent::AllOrNothingActivated);
-                        self.round += 1;
-                        self.bets.clear();
-                        self.round_started_at = timestamp;
-                        self.phase = GamePhase::Betting;
-                    }
-                    _ => {
-                        // Minigame: emit MinigameReady and transition to InMinigame for StartMinigame
-                        if let Some(minigame_type) = self.minigames.first() {
-                            events.push(GameEvent::MinigameReady {
-                                minigame_type: minigame_type.0.clone(),
-                            });
-                            self.phase = GamePhase::StartMinigame(minigame_type.clone());
-                        } else {
-                            // TODO: should be impossible
-                            return Err(anyhow!("No minigame available"));
-                        }
-                    }
+                self.apply_wheel_effect(&effect, timestamp, &mut events)?;
+            }
+
+            // Board Phase
+            (GamePhase::BoardMoves, GameAction::RollDice) => {
+                if self.players.get(self.turn).map(|p| &p.id) != Some(caller) {
+                    return Err(anyhow!("It is not {}'s turn", caller));
                 }
+                self.roll_and_move(&mut events)?;
+            }
+
+            (GamePhase::BoardMoves, GameAction::EndTurn) => {
+                let is_current_player = self.players.get(self.turn).map(|p| &p.id) == Some(caller);
+                let turn_timed_out = timestamp.saturating_sub(self.round_started_at)
+                    > self.config.turn_timeout_ms as u128;
+                let may_end_turn =
+                    is_current_player || (turn_timed_out && caller == backend_identity);
+                if !may_end_turn {
+                    return Err(anyhow!("It is not {}'s turn", caller));
+                }
+                // Players who let their turn time out still move.
+                if !self.has_rolled {
+                    self.roll_and_move(&mut events)?;
+                }
+                self.next_turn(timestamp, &mut events)?;
             }
 
             (
                 GamePhase::StartMinigame(expected_minigame),
-                GameAction::StartMinigame { minigame, players },
+                GameAction::StartMinigame {
+                    minigame,
+                    players,
+                    seed,
+                },
             ) => {
                 // Check the starting state is valid.
                 if expected_minigame != minigame {
@@ -515,6 +1086,9 @@ impl GameState {
                 if minigame_players != players {
                     return Err(anyhow!("Minigame players mismatch"));
                 }
+                if seed.is_some_and(|seed| seed != self.minigame_seed()) {
+                    return Err(anyhow!("Minigame seed mismatch"));
+                }
                 events.push(GameEvent::MinigameStarted {
                     minigame_type: minigame.0.clone(),
                 });
@@ -523,7 +1097,11 @@ impl GameState {
 
             (
                 GamePhase::FinalMinigame(final_minigame),
-                GameAction::StartMinigame { minigame, players },
+                GameAction::StartMinigame {
+                    minigame,
+                    players,
+                    seed,
+                },
             ) => {
                 // Check the starting state is valid.
                 if minigame != final_minigame {
@@ -533,6 +1111,9 @@ impl GameState {
                 if minigame_players != players {
                     return Err(anyhow!("Minigame players mismatch"));
                 }
+                if seed.is_some_and(|seed| seed != self.minigame_seed()) {
+                    return Err(anyhow!("Minigame seed mismatch"));
+                }
                 events.push(GameEvent::MinigameStarted {
                     minigame_type: minigame.0.clone(),
                 });
@@ -561,7 +1142,7 @@ impl GameState {
                 events.push(GameEvent::MinigameEnded { result });
 
                 // End the game if the round limit is reached
-                if self.round >= ROUNDS - 1 {
+                if self.round >= self.config.rounds - 1 {
                     let winner = self
                         .players
                         .iter()
@@ -573,12 +1154,83 @@ impl GameState {
                     });
                     self.phase = GamePhase::RewardsDistribution;
                 } else {
-                    self.round += 1;
-                    self.bets.clear();
-                    self.round_started_at = timestamp;
-                    self.phase = GamePhase::Betting;
+                    self.start_next_round(timestamp);
                 }
             }
+            (phase, GameAction::LeaveGame) => {
+                let Some(index) = self.players.iter().position(|p| p.id == *caller) else {
+                    return Err(anyhow!("Player {} not found", caller));
+                };
+                match phase {
+                    // Nothing has been played yet: the deposit is refunded, checked in lib.rs.
+                    GamePhase::Registration => {
+                        let player = self.players.remove(index);
+                        events.push(GameEvent::PlayerLeft {
+                            player_id: player.id,
+                            refund: player.deposit,
+                        });
+                    }
+                    // Once the game started, leaving forfeits both coins and deposit.
+                    GamePhase::Betting | GamePhase::WheelSpin | GamePhase::BoardMoves => {
+                        if self.players[index].coins == 0 {
+                            return Err(anyhow!("Player {} is out of the game (no coins)", caller));
+                        }
+                        self.players[index].coins = 0;
+                        self.players[index].deposit = 0;
+                        self.bets.remove(caller);
+                        events.push(GameEvent::PlayerLeft {
+                            player_id: caller.clone(),
+                            refund: 0,
+                        });
+                        if self.check_and_handle_game_over(&mut events) {
+                            return Ok(events);
+                        }
+                        if phase == GamePhase::Betting {
+                            self.close_betting_if_done(&mut events)?;
+                        } else if phase == GamePhase::BoardMoves && self.turn == index {
+                            self.next_turn(timestamp, &mut events)?;
+                        }
+                    }
+                    // Minigame results and rewards still refer to the player.
+                    _ => {
+                        return Err(anyhow!("Cannot leave the game during {:?}", phase));
+                    }
+                }
+            }
+
+            (phase, GameAction::AdvancePhase) => {
+                if !self.players.iter().any(|p| p.id == *caller) {
+                    return Err(anyhow!("Only players at this table can advance the game"));
+                }
+                if timestamp <= self.deadline {
+                    return Err(anyhow!("The {:?} phase is not over yet", phase));
+                }
+                let action = match phase {
+                    GamePhase::Registration => GameAction::StartGame,
+                    GamePhase::Betting | GamePhase::WheelSpin => GameAction::SpinWheel,
+                    GamePhase::BoardMoves => GameAction::EndTurn,
+                    _ => return Err(anyhow!("The {:?} phase cannot be advanced", phase)),
+                };
+                if action == GameAction::SpinWheel && !self.seed_revealed {
+                    if timestamp <= self.reveal_deadline {
+                        return Err(anyhow!("The backend can still reveal the dice seed"));
+                    }
+                    self.seed_from_entropy();
+                    events.push(GameEvent::SeedRevealTimedOut);
+                }
+                events.push(GameEvent::PhaseAdvanced {
+                    player_id: caller.clone(),
+                });
+                // Run the same transition the backend would have sent.
+                events.extend(self.process_action(
+                    backend_identity,
+                    backend_identity,
+                    uuid,
+                    action,
+                    timestamp,
+                )?);
+            }
+
             // Rewards Distribution Phase
             (GamePhase::RewardsDistribution, GameAction::DistributeRewards) => {
                 // Distribution is validated in lib.rs
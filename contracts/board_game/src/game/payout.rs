@@ -0,0 +1,258 @@
+use anyhow::{anyhow, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use sdk::Identity;
+use serde::{Deserialize, Serialize};
+
+use super::amount::{self, Amount};
+use super::{GameConfig, Player};
+
+/// Basis points in 100%.
+pub const BPS: u16 = 10_000;
+/// The house never takes more than this, whatever the configuration.
+pub const MAX_HOUSE_FEE_BPS: u16 = 2_000;
+
+/// How the pot (the sum of every player's final coins) is paid out in `oxygen`.
+#[derive(
+    Default, Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq,
+)]
+pub enum PayoutPolicy {
+    /// The player with the most coins takes the whole pot.
+    WinnerTakesAll,
+    /// The best players split the pot, the first share going to the winner.
+    TopN { shares_bps: Vec<u16> },
+    /// Each player gets a share of the pot matching their coins.
+    #[default]
+    Proportional,
+}
+
+pub fn validate(policy: &PayoutPolicy, house_fee_bps: u16, max_players: usize) -> Result<()> {
+    if house_fee_bps > MAX_HOUSE_FEE_BPS {
+        return Err(anyhow!(
+            "House fee cannot exceed {} basis points",
+            MAX_HOUSE_FEE_BPS
+        ));
+    }
+    if let PayoutPolicy::TopN { shares_bps } = policy {
+        if shares_bps.is_empty() || shares_bps.len() > max_players {
+            return Err(anyhow!(
+                "Top-N payouts must have between 1 and {} shares",
+                max_players
+            ));
+        }
+        if shares_bps.contains(&0) {
+            return Err(anyhow!("Top-N shares cannot be zero"));
+        }
+        if shares_bps.iter().map(|s| *s as u32).sum::<u32>() != BPS as u32 {
+            return Err(anyhow!("Top-N shares must add up to {} basis points", BPS));
+        }
+    }
+    Ok(())
+}
+
+/// The `oxygen` transfers owed once the game is over, as (recipient, amount).
+/// The contract checks these exact transfers, and the backend sends them.
+/// The house fee goes to `treasury`, the board's own backend identity.
+pub fn reward_transfers(
+    players: &[Player],
+    config: &GameConfig,
+    treasury: &Identity,
+) -> Result<Vec<(Identity, Amount)>> {
+    let pot = players
+        .iter()
+        .try_fold(0, |pot, p| amount::checked_add(pot, p.coins))?;
+    if pot == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Richest first, registration order breaks ties.
+    let mut ranking: Vec<&Player> = players.iter().filter(|p| p.coins > 0).collect();
+    ranking.sort_by(|a, b| b.coins.cmp(&a.coins));
+
+    let fee = share(pot, config.house_fee_bps as Amount, BPS as Amount)?;
+    let prize = amount::checked_sub(pot, fee)?;
+
+    let mut payouts: Vec<(Identity, Amount)> = match &config.payout {
+        PayoutPolicy::WinnerTakesAll => vec![(ranking[0].id.clone(), prize)],
+        PayoutPolicy::TopN { shares_bps } => {
+            // With fewer players than shares, the unused shares are spread over the others.
+            let paid = ranking.len().min(shares_bps.len());
+            let total_bps: Amount = shares_bps[..paid].iter().map(|s| *s as Amount).sum();
+            ranking[..paid]
+                .iter()
+                .zip(shares_bps)
+                .map(|(p, bps)| Ok((p.id.clone(), share(prize, *bps as Amount, total_bps)?)))
+                .collect::<Result<_>>()?
+        }
+        PayoutPolicy::Proportional => ranking
+            .iter()
+            .map(|p| Ok((p.id.clone(), share(prize, p.coins, pot)?)))
+            .collect::<Result<_>>()?,
+    };
+
+    // Rounding dust goes to the winner, so that the whole pot is paid out.
+    let paid = payouts
+        .iter()
+        .try_fold(0, |total, (_, a)| amount::checked_add(total, *a))?;
+    payouts[0].1 = amount::checked_add(payouts[0].1, amount::checked_sub(prize, paid)?)?;
+
+    match payouts.iter_mut().find(|(id, _)| id == treasury) {
+        Some((_, a)) => *a = amount::checked_add(*a, fee)?,
+        None => payouts.push((treasury.clone(), fee)),
+    }
+    payouts.retain(|(_, a)| *a > 0);
+    Ok(payouts)
+}
+
+/// `total * numerator / denominator`, rounded down.
+fn share(total: Amount, numerator: Amount, denominator: Amount) -> Result<Amount> {
+    if denominator == 0 {
+        return Err(anyhow!("Cannot split an amount in zero parts"));
+    }
+    Ok(amount::checked_mul(total, numerator)? / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(id: &str, coins: Amount) -> Player {
+        Player {
+            id: Identity::new(id),
+            name: id.to_string(),
+            position: 0,
+            coins,
+            deposit: 0,
+            used_uuids: Vec::new(),
+        }
+    }
+
+    fn config(payout: PayoutPolicy, house_fee_bps: u16) -> GameConfig {
+        GameConfig {
+            payout,
+            house_fee_bps,
+            ..GameConfig::default()
+        }
+    }
+
+    fn transfers(
+        players: &[Player],
+        payout: PayoutPolicy,
+        house_fee_bps: u16,
+    ) -> Vec<(String, Amount)> {
+        reward_transfers(
+            players,
+            &config(payout, house_fee_bps),
+            &Identity::new("house"),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|(id, amount)| (id.0, amount))
+        .collect()
+    }
+
+    fn table() -> Vec<Player> {
+        vec![player("a", 50), player("b", 30), player("c", 20)]
+    }
+
+    fn owed(pairs: &[(&str, Amount)]) -> Vec<(String, Amount)> {
+        pairs.iter().map(|(id, a)| (id.to_string(), *a)).collect()
+    }
+
+    #[test]
+    fn winner_takes_all_but_the_fee() {
+        assert_eq!(
+            transfers(&table(), PayoutPolicy::WinnerTakesAll, 250),
+            owed(&[("a", 98), ("house", 2)])
+        );
+    }
+
+    #[test]
+    fn top_n_dust_goes_to_the_winner() {
+        let policy = PayoutPolicy::TopN {
+            shares_bps: vec![6_000, 3_000, 1_000],
+        };
+        assert_eq!(
+            transfers(&table(), policy, 250),
+            owed(&[("a", 60), ("b", 29), ("c", 9), ("house", 2)])
+        );
+    }
+
+    #[test]
+    fn top_n_spreads_unused_shares() {
+        let policy = PayoutPolicy::TopN {
+            shares_bps: vec![5_000, 3_000, 2_000],
+        };
+        let players = vec![player("a", 50), player("b", 30)];
+        assert_eq!(
+            transfers(&players, policy, 0),
+            owed(&[("a", 50), ("b", 30)])
+        );
+    }
+
+    #[test]
+    fn proportional_dust_goes_to_the_winner() {
+        assert_eq!(
+            transfers(&table(), PayoutPolicy::Proportional, 250),
+            owed(&[("a", 50), ("b", 29), ("c", 19), ("house", 2)])
+        );
+    }
+
+    #[test]
+    fn payouts_add_up_to_the_pot() {
+        let policies = [
+            PayoutPolicy::WinnerTakesAll,
+            PayoutPolicy::TopN {
+                shares_bps: vec![7_000, 2_000, 1_000],
+            },
+            PayoutPolicy::Proportional,
+        ];
+        let players = vec![
+            player("a", 333),
+            player("b", 333),
+            player("c", 1),
+            player("d", 0),
+            player("e", 97),
+        ];
+        for policy in policies {
+            for fee in [0, 1, 250, 999, MAX_HOUSE_FEE_BPS] {
+                let paid: Amount = transfers(&players, policy.clone(), fee)
+                    .iter()
+                    .map(|(_, a)| a)
+                    .sum();
+                assert_eq!(paid, 764, "{:?} with a {} bps fee", policy, fee);
+            }
+        }
+    }
+
+    #[test]
+    fn players_without_coins_get_nothing() {
+        let players = vec![player("a", 10), player("b", 0)];
+        assert_eq!(
+            transfers(&players, PayoutPolicy::Proportional, 0),
+            owed(&[("a", 10)])
+        );
+        let broke = vec![player("a", 0), player("b", 0)];
+        assert!(transfers(&broke, PayoutPolicy::WinnerTakesAll, 250).is_empty());
+    }
+
+    #[test]
+    fn ties_go_to_the_first_registered() {
+        let players = vec![player("a", 10), player("b", 20), player("c", 20)];
+        assert_eq!(
+            transfers(&players, PayoutPolicy::WinnerTakesAll, 0),
+            owed(&[("b", 50)])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_policies() {
+        assert!(validate(&PayoutPolicy::Proportional, MAX_HOUSE_FEE_BPS, 4).is_ok());
+        assert!(validate(&PayoutPolicy::Proportional, MAX_HOUSE_FEE_BPS + 1, 4).is_err());
+        let top = |shares_bps: Vec<u16>| PayoutPolicy::TopN { shares_bps };
+        assert!(validate(&top(vec![6_000, 4_000]), 0, 4).is_ok());
+        assert!(validate(&top(vec![6_000, 3_000]), 0, 4).is_err());
+        assert!(validate(&top(vec![10_000, 0]), 0, 4).is_err());
+        assert!(validate(&top(vec![]), 0, 4).is_err());
+        assert!(validate(&top(vec![2_000; 5]), 0, 4).is_err());
+    }
+}
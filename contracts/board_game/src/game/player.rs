@@ -1,25 +1,22 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use super::amount::{self, Amount, AmountDelta};
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerState {
     pub position: usize,
-    pub coins: i32,
+    pub coins: Amount,
 }
 
 impl PlayerState {
-    pub fn add_coins(&mut self, amount: i32) -> Result<i32> {
-        let new_amount = self
-            .coins
-            .checked_add(amount)
-            .ok_or_else(|| anyhow!("Coin overflow"))?;
-
-        if new_amount < 0 {
-            self.coins = 0;
-            Ok(self.coins)
+    /// Applies `delta`, coins never go below zero.
+    pub fn add_coins(&mut self, delta: AmountDelta) -> Result<Amount> {
+        self.coins = if delta >= 0 {
+            amount::checked_add(self.coins, delta.unsigned_abs())?
         } else {
-            self.coins = new_amount;
-            Ok(self.coins)
-        }
+            self.coins - delta.unsigned_abs().min(self.coins)
+        };
+        Ok(self.coins)
     }
 }
@@ -16,14 +16,61 @@ impl std::fmt::Display for GameEvent {
                 if *amount > 0 {
                     write!(f, "Player {} gained {} coins", player_id, amount)
                 } else {
-                    write!(f, "Player {} lost {} coins", player_id, amount.abs())
+                    write!(
+                        f,
+                        "Player {} lost {} coins",
+                        player_id,
+                        amount.unsigned_abs()
+                    )
                 }
             }
             GameEvent::BetPlaced { player_id, amount } => {
                 write!(f, "Player {} placed a bet of {}", player_id, amount)
             }
-            GameEvent::WheelSpun { round, outcome } => {
-                write!(f, "Wheel spun for round {}, outcome: {}", round, outcome)
+            GameEvent::WheelSpun {
+                round,
+                outcome,
+                effect,
+            } => {
+                write!(
+                    f,
+                    "Wheel spun for round {}, outcome: {} ({:?})",
+                    round, outcome, effect
+                )
+            }
+            GameEvent::PlayersSwappedCoins { swaps } => {
+                let pairs: Vec<String> = swaps
+                    .iter()
+                    .map(|(a, b)| format!("{} and {}", a, b))
+                    .collect();
+                write!(f, "Players swapped coins: {}", pairs.join(", "))
+            }
+            GameEvent::AllOrNothingActivated => {
+                write!(
+                    f,
+                    "All or nothing: players must bet all their coins this round"
+                )
+            }
+            GameEvent::TaxCollected { percent } => {
+                write!(f, "Every player paid a {}% tax", percent)
+            }
+            GameEvent::BonusAwarded { amount } => {
+                write!(f, "Every player received a bonus of {} coins", amount)
+            }
+            GameEvent::BetsDoubled => {
+                write!(f, "All bets were doubled")
+            }
+            GameEvent::RoundSkipped { round } => {
+                write!(f, "Round {} was skipped", round)
+            }
+            GameEvent::PlayerLeft { player_id, refund } => {
+                write!(f, "Player {} left, refunded {}", player_id, refund)
+            }
+            GameEvent::PhaseAdvanced { player_id } => {
+                write!(f, "Player {} advanced the game", player_id)
+            }
+            GameEvent::SeedRevealTimedOut => {
+                write!(f, "The dice seed was not revealed in time")
             }
             GameEvent::MinigameReady { minigame_type } => {
                 write!(f, "Minigame '{}' is ready", minigame_type)
@@ -37,6 +84,17 @@ impl std::fmt::Display for GameEvent {
             GameEvent::TurnEnded { next_player } => {
                 write!(f, "Turn ended, next player is {}", next_player)
             }
+            GameEvent::LandedOnTile {
+                player_id,
+                position,
+                tile,
+            } => {
+                write!(
+                    f,
+                    "Player {} landed on tile {} ({:?})",
+                    player_id, position, tile
+                )
+            }
             GameEvent::GameEnded {
                 winner_id,
                 final_coins,
@@ -47,8 +105,15 @@ impl std::fmt::Display for GameEvent {
                     winner_id, final_coins
                 )
             }
-            GameEvent::GameInitialized { random_seed } => {
-                write!(f, "Game initialized, random seed {}", random_seed)
+            GameEvent::GameInitialized { seed_commitment } => {
+                write!(
+                    f,
+                    "Game initialized, seed commitment {:02x?}",
+                    seed_commitment
+                )
+            }
+            GameEvent::SeedRevealed { seed } => {
+                write!(f, "Dice seed revealed: {:02x?}", seed)
             }
             GameEvent::PlayerRegistered { name, player_id } => {
                 write!(f, "Player {} registered as {}", name, player_id)
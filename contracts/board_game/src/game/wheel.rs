@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::amount::Amount;
+
+/// Most segments a wheel can have, the outcome is reported as a `u8`.
+pub const MAX_SEGMENTS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+pub enum WheelEffect {
+    Nothing,
+    /// Every bet is taken from its owner and paid out to a random player.
+    RandomPayout,
+    /// Players must bet all their coins next round.
+    AllOrNothing,
+    /// A minigame is played once everyone has moved on the board.
+    Minigame,
+    /// Players are paired at random and swap their coins.
+    CoinSwap,
+    /// Every player loses a share of their coins.
+    Tax {
+        percent: u8,
+    },
+    /// Every player gains some coins.
+    Bonus {
+        amount: Amount,
+    },
+    /// Bets are doubled (up to what each player owns) and played in a minigame.
+    DoubleBets,
+    /// Nobody moves on the board, the next round starts right away.
+    SkipRound,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+pub struct WheelSegment {
+    /// Relative odds of landing on this segment.
+    pub weight: u32,
+    pub effect: WheelEffect,
+}
+
+impl WheelSegment {
+    pub fn new(weight: u32, effect: WheelEffect) -> Self {
+        Self { weight, effect }
+    }
+}
+
+/// The original five-slot wheel.
+pub fn default_wheel() -> Vec<WheelSegment> {
+    vec![
+        WheelSegment::new(1, WheelEffect::Nothing),
+        WheelSegment::new(1, WheelEffect::RandomPayout),
+        WheelSegment::new(1, WheelEffect::AllOrNothing),
+        WheelSegment::new(1, WheelEffect::Minigame),
+        WheelSegment::new(1, WheelEffect::Minigame),
+    ]
+}
+
+pub fn validate(wheel: &[WheelSegment], max_deposit: Amount) -> Result<()> {
+    if wheel.is_empty() || wheel.len() > MAX_SEGMENTS {
+        return Err(anyhow!(
+            "The wheel must have between 1 and {} segments",
+            MAX_SEGMENTS
+        ));
+    }
+    if wheel.iter().all(|segment| segment.weight == 0) {
+        return Err(anyhow!(
+            "At least one wheel segment must have a non-zero weight"
+        ));
+    }
+    for segment in wheel {
+        match segment.effect {
+            WheelEffect::Tax { percent } if percent > 100 => {
+                return Err(anyhow!("Wheel tax cannot exceed 100%"));
+            }
+            WheelEffect::Bonus { amount } if amount > max_deposit => {
+                return Err(anyhow!("Wheel bonus cannot exceed the max deposit"));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
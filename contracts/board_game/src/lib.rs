@@ -1,7 +1,8 @@
 pub mod game;
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use game::{GameAction, GamePhase, GameState};
+use game::{GameAction, GamePhase, GameState, MinigameResult, MinigameSetup, TableId};
+use minigame_interface::BoardProtocol;
 use sdk::{
     utils::parse_calldata, Blob, BlobData, BlobIndex, Calldata, ContractAction, ContractName,
     Identity, LaneId, RunResult, StateCommitment, StructuredBlobData, ZkContract,
@@ -11,7 +12,8 @@ use smt_token::SmtTokenAction;
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
 // First string is a UUID just to avoid having the same blob hashes.
-pub struct GameActionBlob(pub u128, pub GameAction);
+// The second field selects the table the action applies to.
+pub struct GameActionBlob(pub u128, pub TableId, pub GameAction);
 
 impl ContractAction for GameActionBlob {
     fn as_blob(
@@ -31,6 +33,30 @@ impl ContractAction for GameActionBlob {
     }
 }
 
+impl BoardProtocol for GameActionBlob {
+    fn start_minigame(
+        uuid: u128,
+        table_id: TableId,
+        minigame: ContractName,
+        players: MinigameSetup,
+        seed: Option<[u8; 32]>,
+    ) -> Self {
+        GameActionBlob(
+            uuid,
+            table_id,
+            GameAction::StartMinigame {
+                minigame,
+                players,
+                seed,
+            },
+        )
+    }
+
+    fn end_minigame(uuid: u128, table_id: TableId, result: MinigameResult) -> Self {
+        GameActionBlob(uuid, table_id, GameAction::EndMinigame { result })
+    }
+}
+
 fn check_blob_in_calldata(
     calldata: &Calldata,
     contract_name: ContractName,
@@ -58,25 +84,25 @@ impl ZkContract for GameState {
         // For Minigame actions, verify the caller matches the minigame contract
         // The data is validated when processing the action, and is only repeated here
         // so the minigame can use that as a source of truth for composition.
-        if let GameAction::StartMinigame { .. } = &action.1 {
-            if let GamePhase::StartMinigame(minigame) = &self.phase {
-                // Verify that the caller matches the minigame contract name
-                if exec_ctx.caller.0 != minigame.0 {
-                    return Err("Invalid caller for StartMinigame action".into());
-                }
-            } else if let GamePhase::FinalMinigame(minigame) = &self.phase {
-                if exec_ctx.caller.0 != minigame.0 {
-                    return Err("Invalid caller for FinalMinigame action".into());
+        if let GameAction::StartMinigame { .. } = &action.2 {
+            let table = self.table(action.1).map_err(|e| e.to_string())?;
+            match &table.phase {
+                GamePhase::StartMinigame(minigame) | GamePhase::FinalMinigame(minigame) => {
+                    minigame_interface::verify_caller(&exec_ctx, minigame)
+                        .map_err(|e| e.to_string())?;
                 }
-            } else {
-                return Err("Invalid phase for StartMinigame action".into());
+                _ => return Err("Invalid phase for StartMinigame action".into()),
             }
-        } else if let GameAction::EndMinigame { result } = &action.1 {
-            // Verify that the caller matches the minigame contract name
-            if exec_ctx.caller.0 != result.contract_name.0 {
-                return Err("Invalid caller for EndMinigame action".into());
+        } else if let GameAction::EndMinigame { result } = &action.2 {
+            let table = self.table(action.1).map_err(|e| e.to_string())?;
+            match &table.phase {
+                GamePhase::InMinigame(minigame) if *minigame == result.contract_name => {
+                    minigame_interface::verify_caller(&exec_ctx, minigame)
+                        .map_err(|e| e.to_string())?;
+                }
+                _ => return Err("Invalid phase for EndMinigame action".into()),
             }
-        } else if let GameAction::RegisterPlayer { deposit, .. } = &action.1 {
+        } else if let GameAction::RegisterPlayer { deposit, .. } = &action.2 {
             // Ensure player is depositing the correct amount of coins
             check_blob_in_calldata(
                 contract_input,
@@ -84,19 +110,58 @@ impl ZkContract for GameState {
                 SmtTokenAction::Transfer {
                     sender: contract_input.identity.clone(),
                     recipient: Identity::new(exec_ctx.contract_name.clone().0),
-                    amount: *deposit as u128,
+                    amount: *deposit,
                 },
             )?;
-        } else if let GameAction::DistributeRewards = &action.1 {
-            // Check that we have a transfer blob for all players
-            for player in &self.players {
+        } else if let GameAction::LeaveGame = &action.2 {
+            // Players leaving before the game started get their deposit back
+            let table = self.table(action.1).map_err(|e| e.to_string())?;
+            if table.phase == GamePhase::Registration {
+                if let Some(player) = table
+                    .players
+                    .iter()
+                    .find(|p| p.id == contract_input.identity)
+                {
+                    check_blob_in_calldata(
+                        contract_input,
+                        ContractName::new("oranj"),
+                        SmtTokenAction::Transfer {
+                            sender: Identity::new(exec_ctx.contract_name.clone().0),
+                            recipient: player.id.clone(),
+                            amount: player.deposit,
+                        },
+                    )?;
+                }
+            }
+        } else if let GameAction::EndGame = &action.2 {
+            // Ending an unfinished game refunds the players still in it
+            if let Ok(table) = self.table(action.1) {
+                for (player_id, amount) in table.refunds() {
+                    check_blob_in_calldata(
+                        contract_input,
+                        ContractName::new("oranj"),
+                        SmtTokenAction::Transfer {
+                            sender: Identity::new(exec_ctx.contract_name.clone().0),
+                            recipient: player_id,
+                            amount,
+                        },
+                    )?;
+                }
+            }
+        } else if let GameAction::DistributeRewards = &action.2 {
+            // Check that we have a transfer blob for every payout of the table
+            let table = self.table(action.1).map_err(|e| e.to_string())?;
+            let transfers = table
+                .reward_transfers(&self.backend_identity)
+                .map_err(|e| e.to_string())?;
+            for (recipient, amount) in transfers {
                 check_blob_in_calldata(
                     contract_input,
                     ContractName::new("oxygen"),
                     SmtTokenAction::Transfer {
                         sender: Identity::new(exec_ctx.contract_name.clone().0),
-                        recipient: player.id.clone(),
-                        amount: player.coins as u128,
+                        recipient,
+                        amount,
                     },
                 )?;
             }
@@ -118,12 +183,11 @@ impl ZkContract for GameState {
                 &contract_input.identity,
                 action.0,
                 action.1,
+                action.2,
                 ctx.timestamp.0,
             )
             .map_err(|e| e.to_string())?;
 
-        self.last_interaction_time = ctx.timestamp.0;
-
         Ok((borsh::to_vec(&events).unwrap(), exec_ctx, vec![]))
     }
 
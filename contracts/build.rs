@@ -21,4 +21,40 @@ fn main() {
             ..Default::default()
         },
     );
+    println!("cargo:rerun-if-changed=auction_game/src");
+    build_program_with_args(
+        "./auction_game",
+        BuildArgs {
+            features: vec!["sp1".to_string()],
+            output_directory: Some("../elf".to_string()),
+            ..Default::default()
+        },
+    );
+    println!("cargo:rerun-if-changed=dice_duel/src");
+    build_program_with_args(
+        "./dice_duel",
+        BuildArgs {
+            features: vec!["sp1".to_string()],
+            output_directory: Some("../elf".to_string()),
+            ..Default::default()
+        },
+    );
+    println!("cargo:rerun-if-changed=resource_split/src");
+    build_program_with_args(
+        "./resource_split",
+        BuildArgs {
+            features: vec!["sp1".to_string()],
+            output_directory: Some("../elf".to_string()),
+            ..Default::default()
+        },
+    );
+    println!("cargo:rerun-if-changed=lottery/src");
+    build_program_with_args(
+        "./lottery",
+        BuildArgs {
+            features: vec!["sp1".to_string()],
+            output_directory: Some("../elf".to_string()),
+            ..Default::default()
+        },
+    );
 }
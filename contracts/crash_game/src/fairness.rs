@@ -0,0 +1,102 @@
+//! Provably fair crash points.
+//!
+//! The backend commits to the end of a hash chain when the contract is registered,
+//! then reveals the chain backwards, one pre-image per round. Each round's crash point
+//! is derived from its pre-image, so it is fixed before anyone bets and can be checked by anyone.
+//! Rounds played side by side claim their element when they are set up, and may reveal them in any order.
+
+use sha2::{Digest, Sha256};
+
+use crate::multiplier::{self, Multiplier};
+
+/// One round in this many crashes instantly, which is the house edge.
+const INSTANT_CRASH_ODDS: u64 = 33;
+
+pub fn hash(seed: &[u8; 32]) -> [u8; 32] {
+    Sha256::digest(seed).into()
+}
+
+/// Whether `seed` is the pre-image of the current chain `head`.
+pub fn is_preimage(seed: &[u8; 32], head: &[u8; 32]) -> bool {
+    hash(seed) == *head
+}
+
+/// The element `length` hashes away from `seed`, committed to as the chain head.
+pub fn chain_end(seed: [u8; 32], length: u32) -> [u8; 32] {
+    (0..length).fold(seed, |current, _| hash(&current))
+}
+
+/// The multiplier at which a round revealing `seed` crashes.
+pub fn crash_point(seed: &[u8; 32]) -> Multiplier {
+    let digest = hash(seed);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    // 52 bits, so that the ratio below stays exact.
+    let h = u64::from_be_bytes(bytes) >> 12;
+    if h % INSTANT_CRASH_ODDS == 0 {
+        return multiplier::ONE;
+    }
+    let e = 1u128 << 52;
+    // Rounded down to the hundredth.
+    let hundredths = (100 * e - h as u128) / (e - h as u128);
+    (hundredths * (multiplier::ONE as u128 / 100)).min(multiplier::MAX as u128) as Multiplier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first `count` elements of a chain starting from a fixed seed.
+    fn seeds(count: usize) -> Vec<[u8; 32]> {
+        std::iter::successors(Some([0u8; 32]), |seed| Some(hash(seed)))
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn crash_points_stay_in_bounds() {
+        for seed in seeds(10_000) {
+            let crash_point = crash_point(&seed);
+            assert!((multiplier::ONE..=multiplier::MAX).contains(&crash_point));
+            assert_eq!(crash_point % 100, 0, "not rounded to the hundredth");
+        }
+    }
+
+    #[test]
+    fn crash_points_follow_the_house_edge() {
+        let points: Vec<Multiplier> = seeds(10_000).iter().map(crash_point).collect();
+        let instant = points.iter().filter(|p| **p == multiplier::ONE).count();
+        let doubled = points.iter().filter(|p| **p >= 2 * multiplier::ONE).count();
+        // About 1 in 33 instant crashes, plus the points below 1.01x.
+        assert!(
+            (300..=550).contains(&instant),
+            "{} instant crashes",
+            instant
+        );
+        // Just under half of the rounds reach 2x.
+        assert!(
+            (4_500..=5_000).contains(&doubled),
+            "{} rounds at 2x",
+            doubled
+        );
+    }
+
+    #[test]
+    fn chain_end_round_trips() {
+        let seed = [7u8; 32];
+        assert_eq!(chain_end(seed, 0), seed);
+        assert_eq!(chain_end(seed, 1), hash(&seed));
+        assert_eq!(chain_end(chain_end(seed, 3), 4), chain_end(seed, 7));
+        // Each revealed element is the pre-image of the one committed before it.
+        for length in 1..10 {
+            assert!(is_preimage(
+                &chain_end(seed, length - 1),
+                &chain_end(seed, length)
+            ));
+            assert!(!is_preimage(
+                &chain_end(seed, length),
+                &chain_end(seed, length)
+            ));
+        }
+    }
+}
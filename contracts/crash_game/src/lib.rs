@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Result};
-use board_game::game::{MinigameResult, PlayerMinigameResult};
 use board_game::GameActionBlob;
 use borsh::{BorshDeserialize, BorshSerialize};
+use minigame_interface::{amount, Amount, AmountDelta, MinigameSetup, TableId};
+use multiplier::Multiplier;
 use sdk::caller::ExecutionContext;
 use sdk::utils::parse_calldata;
 use sdk::{
@@ -9,16 +10,47 @@ use sdk::{
     StateCommitment, StructuredBlobData, ZkContract,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use smt_token::SmtTokenAction;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+pub mod fairness;
+pub mod multiplier;
 pub mod utils;
 
+/// After this long waiting for the backend, players may start the game themselves.
+pub const START_DEADLINE_MS: u128 = 20_000;
+/// After running this long, players may crash the game themselves, which voids the round.
+pub const RUN_DEADLINE_MS: u128 = 60_000;
+/// Highest multiplier a player can target for an automatic cash-out.
+pub const MAX_AUTO_CASH_OUT: Multiplier = 10_000 * multiplier::ONE;
+/// Smallest stake a player can change their bet to before the start.
+pub const MIN_BET: Amount = 1;
+/// How many settled rounds the history keeps, older ones being dropped.
+pub const HISTORY_LENGTH: usize = 50;
+
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct Player {
     pub id: Identity,
     pub name: String,
-    pub bet: u64,
-    pub cashed_out_at: Option<f64>,
+    pub bet: Amount,
+    // Coins on the board, the most the player can bet.
+    pub coins: Amount,
+    pub cashed_out_at: Option<Multiplier>,
+    // Timestamp of the cash-out, which must come before the curve reached the crash point.
+    pub cashed_out_time: Option<u128>,
+    // Settled when the game crashes, if the crash point is above it.
+    pub auto_cash_out: Option<Multiplier>,
+}
+
+/// A player of the standalone table, playing with oranj deposited in the contract.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Seat {
+    pub id: Identity,
+    pub name: String,
+    // Oranj held for the player, settled after every round and withdrawn when leaving.
+    pub balance: Amount,
+    // Bet carried over to the next round, capped by the balance.
+    pub bet: Amount,
 }
 
 #[derive(
@@ -36,44 +68,136 @@ pub enum MinigameState {
 pub struct MinigameInstanceVerifiable {
     pub state: MinigameState,
     pub players: BTreeMap<Identity, Player>,
+    // Timestamp of the Start transaction, the multiplier curve begins there.
+    pub started_at: u128,
+    // Past this point, the current state can be advanced by any player of the game.
+    pub deadline: u128,
+    // Position of the round's seed in the backend's hash chain, claimed when the round is set up.
+    pub seed_index: u64,
+    // Where the round crashed, kept until it is settled.
+    pub crash_point: Option<Multiplier>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct MinigameInstanceBackend {
-    pub current_multiplier: f64,
+    pub current_multiplier: Multiplier,
     pub game_setup_time: Option<u128>,
     pub game_start_time: Option<u128>,
     pub current_time: Option<u128>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
-pub struct GameState {
+/// One crash round, for a board table or for the standalone table.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Round {
     pub minigame_verifiable: MinigameInstanceVerifiable,
     pub minigame_backend: MinigameInstanceBackend,
-    pub board_contract: ContractName,
+}
+
+/// Which round an action applies to: the board contract and table that started it.
+/// The standalone table has no board.
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub struct RoundKey {
+    pub board: Option<ContractName>,
+    pub table_id: TableId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GameState {
+    // Rounds being played, at most one per board table.
+    pub rounds: BTreeMap<RoundKey, Round>,
+    // Board contracts allowed to start rounds, each of them using up a chain element.
+    pub boards: BTreeSet<ContractName>,
     pub backend_identity: Identity,
+    // Revealed elements of the backend's hash chain by index, 0 being the committed head.
+    // A seed hashes to the closest revealed element below it, so rounds reveal in any order.
+    // Only the elements pending and future rounds hash to are kept.
+    pub revealed_seeds: BTreeMap<u64, [u8; 32]>,
+    // How many chain elements were claimed by rounds. Rounds claim the next element when
+    // they are set up, so the backend never picks which round gets which seed.
+    pub chain_claimed: u64,
+    // How many elements the current chain has, past which no round can be set up.
+    pub chain_length: u32,
+    // How many times the backend moved to a new chain, once the previous one ran low.
+    pub chain_generation: u32,
+    // Players of the standalone table, which runs rounds without a board game.
+    pub seats: BTreeMap<Identity, Seat>,
+    // Settled rounds of every table, oldest first, at most `HISTORY_LENGTH` of them.
+    pub history: VecDeque<RoundSummary>,
     pub last_interaction_time: u128,
     pub lane_id: LaneId,
 }
 
+/// A settled round, as kept in the history.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RoundSummary {
+    pub key: RoundKey,
+    pub crash_point: Multiplier,
+    pub player_count: usize,
+    pub total_wagered: Amount,
+    pub total_paid: Amount,
+}
+
 // Actions that can be performed on-chain
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub enum ChainAction {
+    /// Sets up the round of the board table named by the round key.
     InitMinigame {
-        players: Vec<(Identity, String, u64)>,
+        players: MinigameSetup,
         time: u64,
     },
     Start {
         time: u64,
     },
+    /// Takes a seat at the standalone table, or tops it up, with an oranj transfer to the contract.
+    JoinTable {
+        player_id: Identity,
+        name: String,
+        deposit: Amount,
+    },
+    /// Leaves the standalone table, the balance being transferred back in oranj.
+    LeaveTable {
+        player_id: Identity,
+    },
+    /// Starts a standalone round with everyone seated, in place of a board `InitMinigame`.
+    StartRound {
+        time: u64,
+    },
     CashOut {
         player_id: Identity,
-        multiplier: f64,
+        multiplier: Multiplier,
+    },
+    SetAutoCashOut {
+        player_id: Identity,
+        multiplier: Option<Multiplier>,
+    },
+    PlaceBet {
+        player_id: Identity,
+        amount: Amount,
     },
     Crash {
-        final_multiplier: f64,
+        final_multiplier: Multiplier,
+        // The round's element of the hash chain, ignored when a player forces the crash.
+        seed: [u8; 32],
     },
     Done,
+    /// Commits to a new hash chain once the current one runs low. Only possible while no
+    /// round waits for its seed, as they would not be able to reveal it anymore.
+    RotateChain {
+        head: [u8; 32],
+        length: u32,
+    },
 }
 
 // Events that are recorded on-chain
@@ -83,16 +207,38 @@ pub enum ChainEvent {
         player_count: usize,
     },
     GameStarted,
+    PlayerJoined {
+        player_id: Identity,
+        balance: Amount,
+    },
+    PlayerLeft {
+        player_id: Identity,
+        withdrawn: Amount,
+    },
     PlayerCashedOut {
         player_id: Identity,
-        multiplier: f64,
-        winnings: u64,
+        multiplier: Multiplier,
+        winnings: Amount,
+    },
+    AutoCashOutSet {
+        player_id: Identity,
+        multiplier: Option<Multiplier>,
+    },
+    BetChanged {
+        player_id: Identity,
+        amount: Amount,
     },
     GameCrashed {
-        final_multiplier: f64,
+        final_multiplier: Multiplier,
     },
+    // Crashed by a player past the deadline, the bets still riding are refunded.
+    RoundVoided,
     MinigameEnded {
-        final_results: Vec<(Identity, i32)>,
+        final_results: Vec<(Identity, AmountDelta)>,
+    },
+    ChainRotated {
+        generation: u32,
+        length: u32,
     },
 }
 
@@ -103,29 +249,30 @@ pub enum ServerAction {
 }
 
 // Server-side events for UI updates
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerEvent {
     MultiplierUpdated {
-        multiplier: f64,
+        multiplier: Multiplier,
     },
     InsufficientFunds {
         player_id: Identity,
-        available: u64,
-        requested: u64,
+        available: Amount,
+        requested: Amount,
     },
     InvalidBetAmount {
-        min: u64,
-        max: u64,
-        provided: u64,
+        min: Amount,
+        max: Amount,
+        provided: Amount,
     },
     // Exists just to answer GetEndResults, bit of a hack.
     MinigameEnded {
-        final_results: Vec<(Identity, i32)>,
+        final_results: Vec<(Identity, AmountDelta)>,
     },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
-pub struct ChainActionBlob(pub u128, pub ChainAction);
+// The UUID avoids having the same blob hashes, the key selects the round the action applies to.
+pub struct ChainActionBlob(pub u128, pub RoundKey, pub ChainAction);
 
 impl ContractAction for ChainActionBlob {
     fn as_blob(
@@ -145,6 +292,56 @@ impl ContractAction for ChainActionBlob {
     }
 }
 
+fn check_blob_in_calldata(
+    calldata: &Calldata,
+    contract_name: ContractName,
+    action: SmtTokenAction,
+) -> Result<(), String> {
+    for (_, check_blob) in calldata.blobs.iter() {
+        if check_blob.contract_name != contract_name {
+            continue;
+        };
+        let Ok(blob) = sdk::StructuredBlob::<SmtTokenAction>::try_from(check_blob.clone()) else {
+            continue;
+        };
+        if blob.data.parameters == action {
+            return Ok(());
+        }
+    }
+    Err("Action not found in calldata".into())
+}
+
+impl RoundKey {
+    pub fn board(board: ContractName, table_id: TableId) -> Self {
+        Self {
+            board: Some(board),
+            table_id,
+        }
+    }
+
+    pub fn standalone() -> Self {
+        Self {
+            board: None,
+            table_id: 0,
+        }
+    }
+}
+
+impl Seat {
+    /// The seat as a player of the next round.
+    fn player(&self) -> Player {
+        Player {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            bet: self.bet.min(self.balance),
+            coins: self.balance,
+            cashed_out_at: None,
+            cashed_out_time: None,
+            auto_cash_out: None,
+        }
+    }
+}
+
 impl ZkContract for GameState {
     fn execute(&mut self, contract_input: &Calldata) -> RunResult {
         let (action, mut exec_ctx) =
@@ -159,6 +356,31 @@ impl ZkContract for GameState {
             return Err("Invalid identity provider".to_string());
         }
 
+        // Standalone tables are paid in and out in oranj
+        if let ChainAction::JoinTable { deposit, .. } = &action.2 {
+            check_blob_in_calldata(
+                contract_input,
+                ContractName::new("oranj"),
+                SmtTokenAction::Transfer {
+                    sender: contract_input.identity.clone(),
+                    recipient: Identity::new(exec_ctx.contract_name.clone().0),
+                    amount: *deposit,
+                },
+            )?;
+        } else if let ChainAction::LeaveTable { player_id } = &action.2 {
+            if let Some(seat) = self.seats.get(player_id).filter(|seat| seat.balance > 0) {
+                check_blob_in_calldata(
+                    contract_input,
+                    ContractName::new("oranj"),
+                    SmtTokenAction::Transfer {
+                        sender: Identity::new(exec_ctx.contract_name.clone().0),
+                        recipient: seat.id.clone(),
+                        amount: seat.balance,
+                    },
+                )?;
+            }
+        }
+
         let Some(ref ctx) = contract_input.tx_ctx else {
             return Err("Missing transaction context".into());
         };
@@ -175,6 +397,8 @@ impl ZkContract for GameState {
             .process_chain_action(
                 &contract_input.identity,
                 &action.1,
+                &action.2,
+                ctx.timestamp.0,
                 Some((&action, &mut exec_ctx)),
             )
             .map_err(|e| e.to_string())?;
@@ -186,101 +410,284 @@ impl ZkContract for GameState {
 
     fn commit(&self) -> StateCommitment {
         let mut commitment = self.clone();
-        commitment.minigame_backend = MinigameInstanceBackend::default();
+        for round in commitment.rounds.values_mut() {
+            round.minigame_backend = MinigameInstanceBackend::default();
+        }
         StateCommitment(borsh::to_vec(&commitment).unwrap())
     }
 }
 
 impl GameState {
-    pub fn new(board_contract: ContractName, backend_identity: Identity) -> Self {
+    pub fn new(
+        boards: BTreeSet<ContractName>,
+        backend_identity: Identity,
+        chain_head: [u8; 32],
+        chain_length: u32,
+    ) -> Self {
         Self {
-            minigame_verifiable: MinigameInstanceVerifiable::default(),
-            minigame_backend: MinigameInstanceBackend::default(),
-            board_contract,
+            rounds: BTreeMap::new(),
+            boards,
             backend_identity,
+            revealed_seeds: BTreeMap::from([(0, chain_head)]),
+            chain_claimed: 0,
+            chain_length,
+            chain_generation: 0,
+            seats: BTreeMap::new(),
+            history: VecDeque::new(),
             last_interaction_time: 0,
             lane_id: LaneId::default(),
         }
     }
 
+    pub fn round(&self, key: &RoundKey) -> Result<&Round> {
+        self.rounds
+            .get(key)
+            .ok_or_else(|| anyhow!("No round in progress for {:?}", key))
+    }
+
+    pub fn round_mut(&mut self, key: &RoundKey) -> Result<&mut Round> {
+        self.rounds
+            .get_mut(key)
+            .ok_or_else(|| anyhow!("No round in progress for {:?}", key))
+    }
+
+    /// Sets up a round with `players`, claiming the next element of the hash chain for it.
+    fn setup_round(
+        &mut self,
+        key: &RoundKey,
+        players: impl IntoIterator<Item = Player>,
+        timestamp: u128,
+    ) -> Result<usize> {
+        if self.rounds.contains_key(key) {
+            return Err(anyhow!("Game is already in progress"));
+        }
+        self.chain_claimed = self
+            .chain_claimed
+            .checked_add(1)
+            .filter(|index| *index <= self.chain_length as u64)
+            .ok_or_else(|| anyhow!("Hash chain exhausted, waiting for the backend to rotate it"))?;
+        let round = Round {
+            minigame_verifiable: MinigameInstanceVerifiable {
+                state: MinigameState::WaitingForStart,
+                players: players.into_iter().map(|p| (p.id.clone(), p)).collect(),
+                started_at: 0,
+                deadline: timestamp.saturating_add(START_DEADLINE_MS),
+                seed_index: self.chain_claimed,
+                crash_point: None,
+            },
+            minigame_backend: MinigameInstanceBackend {
+                current_multiplier: multiplier::ONE,
+                ..Default::default()
+            },
+        };
+        let player_count = round.minigame_verifiable.players.len();
+        self.rounds.insert(key.clone(), round);
+        Ok(player_count)
+    }
+
+    /// Checks that `seed` is the chain element claimed by the round, and reveals it.
+    fn reveal_seed(&mut self, key: &RoundKey, seed: &[u8; 32]) -> Result<()> {
+        let seed_index = self.round(key)?.minigame_verifiable.seed_index;
+        if self.revealed_seeds.contains_key(&seed_index) {
+            return Err(anyhow!("The seed of this round was already revealed"));
+        }
+        let Some((anchor_index, anchor)) = self.revealed_seeds.range(..seed_index).next_back()
+        else {
+            return Err(anyhow!("No revealed seed to check against"));
+        };
+        let depth = u32::try_from(seed_index - anchor_index)
+            .map_err(|_| anyhow!("Seed index out of range"))?;
+        if fairness::chain_end(*seed, depth) != *anchor {
+            return Err(anyhow!(
+                "Crash seed does not match the committed hash chain"
+            ));
+        }
+        self.revealed_seeds.insert(seed_index, *seed);
+        self.prune_revealed_seeds();
+        Ok(())
+    }
+
+    /// Drops the revealed elements no round needs anymore: pending rounds check their seed
+    /// against the closest one below, future rounds against the last one.
+    fn prune_revealed_seeds(&mut self) {
+        let mut needed: BTreeSet<u64> = self
+            .rounds
+            .values()
+            .filter_map(|round| {
+                self.revealed_seeds
+                    .range(..round.minigame_verifiable.seed_index)
+                    .next_back()
+                    .map(|(index, _)| *index)
+            })
+            .collect();
+        needed.extend(self.revealed_seeds.keys().next_back());
+        self.revealed_seeds
+            .retain(|index, _| needed.contains(index));
+    }
+
     // Process on-chain actions that need to be recorded
     pub fn process_chain_action(
         &mut self,
         identity: &Identity,
+        key: &RoundKey,
         action: &ChainAction,
+        timestamp: u128,
         ctx: Option<(&ChainActionBlob, &mut ExecutionContext)>,
     ) -> Result<Vec<ChainEvent>> {
         let mut events = Vec::new();
 
         match action {
             ChainAction::InitMinigame { players, .. } => {
-                if self.minigame_verifiable.state != MinigameState::Uninitialized {
-                    return Err(anyhow!("Game is already in progress"));
+                let Some(board) = &key.board else {
+                    return Err(anyhow!("The standalone table is started with StartRound"));
+                };
+                if !self.boards.contains(board) {
+                    return Err(anyhow!("{} is not allowed to start rounds", board));
                 }
 
-                // TODO could just read the other blob directly
                 if let Some((blob, exec_ctx)) = ctx {
-                    // Create a new GameActionBlob with the expected data
-                    let expected_board_blob = GameActionBlob(
-                        blob.0,
-                        board_game::game::GameAction::StartMinigame {
-                            minigame: exec_ctx.contract_name.clone(),
-                            players: players.clone(),
-                        },
-                    );
                     // Check our data matches the board contract
-                    exec_ctx
-                        .is_in_callee_blobs(&self.board_contract, expected_board_blob)
-                        .map_err(|_| {
-                            anyhow!("Missing or incorrect board game StartMinigame action in transaction",)
-                        })?;
-                }
-
-                let player_count = players.len();
-
-                // Initialize or update player states
-                for (id, name, bet) in players {
-                    self.minigame_verifiable.players.insert(
-                        id.clone(),
-                        Player {
-                            id: id.clone(),
-                            name: name.clone(),
-                            bet: *bet,
-                            cashed_out_at: None,
-                        },
-                    );
+                    minigame_interface::verify_board_start::<GameActionBlob>(
+                        exec_ctx,
+                        board,
+                        blob.0,
+                        key.table_id,
+                        players,
+                        None,
+                    )?;
                 }
 
-                self.minigame_verifiable.state = MinigameState::WaitingForStart;
-                self.minigame_backend.current_multiplier = 1.0;
+                let player_count = self.setup_round(
+                    key,
+                    players.iter().map(|(id, name, bet, coins)| Player {
+                        id: id.clone(),
+                        name: name.clone(),
+                        bet: *bet,
+                        coins: *coins,
+                        cashed_out_at: None,
+                        cashed_out_time: None,
+                        auto_cash_out: None,
+                    }),
+                    timestamp,
+                )?;
 
                 events.push(ChainEvent::MinigameInitialized { player_count });
             }
 
             ChainAction::Start { .. } => {
-                if identity != &self.backend_identity {
+                let backend_identity = self.backend_identity.clone();
+                let round = &mut self.round_mut(key)?.minigame_verifiable;
+                if identity != &backend_identity && !round.is_overdue(identity, timestamp) {
                     return Err(anyhow!(
                         "Only the backend can start the game: {} vs {}",
                         identity,
-                        self.backend_identity
+                        backend_identity
                     ));
                 }
 
-                if self.minigame_verifiable.state != MinigameState::WaitingForStart {
+                if round.state != MinigameState::WaitingForStart {
                     return Err(anyhow!("Game is already in progress"));
                 }
 
-                self.minigame_verifiable.state = MinigameState::Running;
-                self.minigame_backend.current_multiplier = 1.0;
+                round.state = MinigameState::Running;
+                round.started_at = timestamp;
+                round.deadline = timestamp.saturating_add(RUN_DEADLINE_MS);
 
                 events.push(ChainEvent::GameStarted);
             }
 
+            ChainAction::JoinTable {
+                player_id,
+                name,
+                deposit,
+            } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+                if *deposit == 0 {
+                    return Err(anyhow!("Deposit must be positive"));
+                }
+
+                let seat = self.seats.entry(player_id.clone()).or_insert(Seat {
+                    id: player_id.clone(),
+                    name: name.clone(),
+                    balance: 0,
+                    bet: 0,
+                });
+                seat.balance = amount::checked_add(seat.balance, *deposit)?;
+                let seat = seat.clone();
+
+                // Players joining during the betting window play this round already.
+                if let Some(round) = self.rounds.get_mut(&RoundKey::standalone()) {
+                    let round = &mut round.minigame_verifiable;
+                    if round.state == MinigameState::WaitingForStart {
+                        round
+                            .players
+                            .entry(seat.id.clone())
+                            .or_insert_with(|| seat.player())
+                            .coins = seat.balance;
+                    }
+                }
+
+                events.push(ChainEvent::PlayerJoined {
+                    player_id: player_id.clone(),
+                    balance: seat.balance,
+                });
+            }
+
+            ChainAction::LeaveTable { player_id } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+
+                if let Some(round) = self.rounds.get_mut(&RoundKey::standalone()) {
+                    let round = &mut round.minigame_verifiable;
+                    if round.players.contains_key(player_id) {
+                        if round.state != MinigameState::WaitingForStart {
+                            return Err(anyhow!("Cannot leave the table during a round"));
+                        }
+                        round.players.remove(player_id);
+                    }
+                }
+
+                let Some(seat) = self.seats.remove(player_id) else {
+                    return Err(anyhow!("Player not seated"));
+                };
+                events.push(ChainEvent::PlayerLeft {
+                    player_id: player_id.clone(),
+                    withdrawn: seat.balance,
+                });
+            }
+
+            ChainAction::StartRound { .. } => {
+                if identity != &self.backend_identity {
+                    return Err(anyhow!(
+                        "Only the backend can start a round: {} vs {}",
+                        identity,
+                        self.backend_identity
+                    ));
+                }
+
+                if *key != RoundKey::standalone() {
+                    return Err(anyhow!("Rounds are only started for the standalone table"));
+                }
+
+                if !self.standalone_bets_placed() {
+                    return Err(anyhow!("Nobody bets on the next round"));
+                }
+
+                let players: Vec<Player> = self.seats.values().map(Seat::player).collect();
+                let player_count = self.setup_round(key, players, timestamp)?;
+
+                events.push(ChainEvent::MinigameInitialized { player_count });
+            }
+
             ChainAction::CashOut {
                 player_id,
                 multiplier,
             } => {
-                if self.minigame_verifiable.state != MinigameState::Running {
+                let round = &mut self.round_mut(key)?.minigame_verifiable;
+                if round.state != MinigameState::Running {
                     return Err(anyhow!("Game is not running"));
                 }
 
@@ -288,7 +695,8 @@ impl GameState {
                     return Err(anyhow!("Player ID does not match the action sender"));
                 }
 
-                let Some(player) = self.minigame_verifiable.players.get_mut(player_id) else {
+                let started_at = round.started_at;
+                let Some(player) = round.players.get_mut(player_id) else {
                     return Err(anyhow!("Player not found"));
                 };
 
@@ -296,9 +704,22 @@ impl GameState {
                     return Err(anyhow!("Bet already cashed out"));
                 }
 
+                // The multiplier can't be ahead of the curve at the time of the transaction,
+                // which is always later than the click itself.
+                let elapsed = timestamp.saturating_sub(started_at);
+                let max_multiplier = multiplier::at(elapsed as u64);
+                if !(multiplier::ONE..=max_multiplier).contains(multiplier) {
+                    return Err(anyhow!(
+                        "Cannot cash out at {}x, the multiplier is at most {}x",
+                        multiplier::format(*multiplier),
+                        multiplier::format(max_multiplier)
+                    ));
+                }
+
                 player.cashed_out_at = Some(*multiplier);
+                player.cashed_out_time = Some(timestamp);
 
-                let winnings = Self::calculate_winnings(player.bet, *multiplier);
+                let winnings = multiplier::winnings(player.bet, *multiplier)?;
                 events.push(ChainEvent::PlayerCashedOut {
                     player_id: player_id.clone(),
                     multiplier: *multiplier,
@@ -306,80 +727,248 @@ impl GameState {
                 });
             }
 
-            ChainAction::Crash { final_multiplier } => {
-                if identity != &self.backend_identity {
+            ChainAction::SetAutoCashOut {
+                player_id,
+                multiplier,
+            } => {
+                let round = &mut self.round_mut(key)?.minigame_verifiable;
+                if round.state != MinigameState::WaitingForStart {
                     return Err(anyhow!(
-                        "Only the backend can start the game: {} vs {}",
+                        "Auto cash-out can only be set before the game starts"
+                    ));
+                }
+
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+
+                if let Some(target) = multiplier {
+                    if !(*target > multiplier::ONE && *target <= MAX_AUTO_CASH_OUT) {
+                        return Err(anyhow!(
+                            "Auto cash-out must be above 1x and at most {}x",
+                            multiplier::format(MAX_AUTO_CASH_OUT)
+                        ));
+                    }
+                }
+
+                let Some(player) = round.players.get_mut(player_id) else {
+                    return Err(anyhow!("Player not found"));
+                };
+
+                player.auto_cash_out = *multiplier;
+                events.push(ChainEvent::AutoCashOutSet {
+                    player_id: player_id.clone(),
+                    multiplier: *multiplier,
+                });
+            }
+
+            ChainAction::PlaceBet { player_id, amount } => {
+                // Between standalone rounds, the bet is set for the next one.
+                if let Some(round) = self.rounds.get(key) {
+                    if round.minigame_verifiable.state != MinigameState::WaitingForStart {
+                        return Err(anyhow!("Bets can only be changed before the game starts"));
+                    }
+                }
+
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+
+                if let Some(error) = self.bet_error(key, player_id, *amount)? {
+                    return Err(anyhow!("Invalid bet: {:?}", error));
+                }
+
+                if let Some(round) = self.rounds.get_mut(key) {
+                    if let Some(player) = round.minigame_verifiable.players.get_mut(player_id) {
+                        player.bet = *amount;
+                    }
+                }
+                // Standalone bets carry over to the following rounds.
+                if key.board.is_none() {
+                    if let Some(seat) = self.seats.get_mut(player_id) {
+                        seat.bet = *amount;
+                    }
+                }
+                events.push(ChainEvent::BetChanged {
+                    player_id: player_id.clone(),
+                    amount: *amount,
+                });
+            }
+
+            ChainAction::Crash {
+                final_multiplier,
+                seed,
+            } => {
+                let is_backend = identity == &self.backend_identity;
+                let round = &self.round(key)?.minigame_verifiable;
+                if !is_backend && !round.is_overdue(identity, timestamp) {
+                    return Err(anyhow!(
+                        "Only the backend can crash the round before its deadline: {} vs {}",
                         identity,
                         self.backend_identity
                     ));
                 }
 
-                if self.minigame_verifiable.state != MinigameState::Running {
+                if round.state != MinigameState::Running {
                     return Err(anyhow!("Game is not running"));
                 }
 
-                self.minigame_verifiable.state = MinigameState::Crashed;
-                self.minigame_backend.current_multiplier = *final_multiplier;
+                // Without the backend the crash point is unknown, a forced crash voids the round.
+                // Cash-outs already made stand, the bets still riding are refunded.
+                if !is_backend {
+                    let round = self.round_mut(key)?;
+                    for player in round.minigame_verifiable.players.values_mut() {
+                        if player.cashed_out_at.is_none() {
+                            player.cashed_out_at = Some(multiplier::ONE);
+                        }
+                    }
+                    round.minigame_verifiable.state = MinigameState::Crashed;
+                    round.minigame_verifiable.crash_point = Some(multiplier::ONE);
+                    round.minigame_backend.current_multiplier = multiplier::ONE;
 
-                events.push(ChainEvent::GameCrashed {
-                    final_multiplier: *final_multiplier,
-                });
+                    events.push(ChainEvent::RoundVoided);
+                    return Ok(events);
+                }
+
+                let crash_point = fairness::crash_point(seed);
+                if *final_multiplier != crash_point {
+                    return Err(anyhow!(
+                        "Crash multiplier {}x does not match the seed's crash point {}x",
+                        multiplier::format(*final_multiplier),
+                        multiplier::format(crash_point)
+                    ));
+                }
+                self.reveal_seed(key, seed)?;
+                let final_multiplier = crash_point;
+
+                let round = self.round_mut(key)?;
+
+                // Nobody can cash out past the crash point, nor once the curve got there.
+                let crashed_at = round
+                    .minigame_verifiable
+                    .started_at
+                    .saturating_add(multiplier::reached_at(final_multiplier) as u128);
+                for player in round.minigame_verifiable.players.values_mut() {
+                    if player.cashed_out_at.is_some_and(|m| m > final_multiplier)
+                        || player.cashed_out_time.is_some_and(|t| t >= crashed_at)
+                    {
+                        player.cashed_out_at = None;
+                        player.cashed_out_time = None;
+                    }
+                }
+
+                // Targets below the crash point were reached, settle them.
+                for player in round.minigame_verifiable.players.values_mut() {
+                    let Some(target) = player.auto_cash_out else {
+                        continue;
+                    };
+                    if player.cashed_out_at.is_some() || target >= final_multiplier {
+                        continue;
+                    }
+                    player.cashed_out_at = Some(target);
+                    events.push(ChainEvent::PlayerCashedOut {
+                        player_id: player.id.clone(),
+                        multiplier: target,
+                        winnings: multiplier::winnings(player.bet, target)?,
+                    });
+                }
+
+                round.minigame_verifiable.state = MinigameState::Crashed;
+                round.minigame_verifiable.crash_point = Some(final_multiplier);
+                round.minigame_backend.current_multiplier = final_multiplier;
+
+                events.push(ChainEvent::GameCrashed { final_multiplier });
             }
 
             ChainAction::Done => {
-                if self.minigame_verifiable.state != MinigameState::Crashed {
+                let round = &self.round(key)?.minigame_verifiable;
+                if round.state != MinigameState::Crashed {
                     return Err(anyhow!("Cannot end minigame while it is still running"));
                 }
-                let expected_final_results = self.final_results();
-                if let Some((blob, exec_ctx)) = ctx {
-                    // Create a new GameActionBlob with the expected data
-                    let expected_board_blob = GameActionBlob(
-                        blob.0,
-                        board_game::game::GameAction::EndMinigame {
-                            result: MinigameResult {
-                                contract_name: exec_ctx.contract_name.clone(),
-                                player_results: expected_final_results
-                                    .iter()
-                                    .map(|r| PlayerMinigameResult {
-                                        player_id: r.0.clone(),
-                                        coins_delta: r.1,
-                                    })
-                                    .collect(),
-                            },
-                        },
-                    );
-
+                let expected_final_results = round.final_results()?;
+                let summary = round.summary(key)?;
+                match (&key.board, ctx) {
+                    // No board to report to, the results go into the seats.
+                    (None, _) => {
+                        for (player_id, delta) in &expected_final_results {
+                            if let Some(seat) = self.seats.get_mut(player_id) {
+                                seat.balance = if *delta >= 0 {
+                                    amount::checked_add(seat.balance, delta.unsigned_abs())?
+                                } else {
+                                    amount::checked_sub(seat.balance, delta.unsigned_abs())?
+                                };
+                            }
+                        }
+                    }
                     // When ending the minigame, verify that the board game is being updated with the correct data
-                    exec_ctx
-                        .is_in_callee_blobs(&self.board_contract, expected_board_blob.clone())
-                        .map_err(|_| {
-                            anyhow!("Missing board game EndMinigame action in transaction, expected: {:?}", expected_board_blob)
-                        })?;
+                    (Some(board), Some((blob, exec_ctx))) => {
+                        minigame_interface::verify_board_end::<GameActionBlob>(
+                            exec_ctx,
+                            board,
+                            blob.0,
+                            key.table_id,
+                            &expected_final_results,
+                        )?;
+                    }
+                    (Some(_), None) => {}
                 }
 
-                self.minigame_verifiable = MinigameInstanceVerifiable::default();
+                self.rounds.remove(key);
+                self.record_round(summary);
                 events.push(ChainEvent::MinigameEnded {
                     final_results: expected_final_results,
                 });
             }
+
+            ChainAction::RotateChain { head, length } => {
+                if identity != &self.backend_identity {
+                    return Err(anyhow!(
+                        "Only the backend can rotate the hash chain: {} vs {}",
+                        identity,
+                        self.backend_identity
+                    ));
+                }
+                if *length == 0 {
+                    return Err(anyhow!("The new hash chain cannot be empty"));
+                }
+                if self.seeds_pending() {
+                    return Err(anyhow!("Some rounds still have to reveal their seed"));
+                }
+
+                self.chain_generation = self
+                    .chain_generation
+                    .checked_add(1)
+                    .ok_or_else(|| anyhow!("Too many hash chains"))?;
+                self.revealed_seeds = BTreeMap::from([(0, *head)]);
+                self.chain_claimed = 0;
+                self.chain_length = *length;
+                events.push(ChainEvent::ChainRotated {
+                    generation: self.chain_generation,
+                    length: *length,
+                });
+            }
         }
 
         Ok(events)
     }
 
     // Process server-side actions for real-time updates
-    pub fn process_server_action(&mut self, action: ServerAction) -> Result<Vec<ServerEvent>> {
+    pub fn process_server_action(
+        &mut self,
+        key: &RoundKey,
+        action: ServerAction,
+    ) -> Result<Vec<ServerEvent>> {
         let mut events = Vec::new();
+        let round = self.round_mut(key)?;
 
         match action {
             ServerAction::Update { current_time } => {
-                if self.minigame_verifiable.state != MinigameState::Running {
+                if round.minigame_verifiable.state != MinigameState::Running {
                     return Ok(events);
                 }
 
-                let new_multiplier = Self::calculate_multiplier(current_time);
-                self.minigame_backend.current_multiplier = new_multiplier;
+                let new_multiplier = multiplier::at(current_time);
+                round.minigame_backend.current_multiplier = new_multiplier;
 
                 events.push(ServerEvent::MultiplierUpdated {
                     multiplier: new_multiplier,
@@ -390,44 +979,128 @@ impl GameState {
         Ok(events)
     }
 
-    fn calculate_multiplier(elapsed_millis: u64) -> f64 {
-        let elapsed_secs = elapsed_millis as f64 / 1000.0;
-        (elapsed_secs * 0.2).exp()
+    /// Keeps a settled round in the history, dropping the oldest one when full.
+    fn record_round(&mut self, summary: RoundSummary) {
+        if self.history.len() >= HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(summary);
+    }
+
+    /// Whether some round still has to reveal its seed, which prevents rotating the chain.
+    pub fn seeds_pending(&self) -> bool {
+        self.rounds
+            .values()
+            .any(|round| round.minigame_verifiable.state != MinigameState::Crashed)
     }
 
-    fn calculate_winnings(bet_amount: u64, multiplier: f64) -> u64 {
-        (bet_amount as f64 * multiplier) as u64
+    /// Whether someone seated at the standalone table bets on the next round. Rounds nobody
+    /// bets on are not started, as each of them uses up an element of the hash chain.
+    pub fn standalone_bets_placed(&self) -> bool {
+        self.seats.values().any(|seat| seat.player().bet > 0)
     }
 
-    pub fn get_end_results(&self) -> Result<Vec<(Identity, i32)>> {
-        if self.minigame_verifiable.state != MinigameState::Crashed {
+    /// Whether the backend should start a standalone round.
+    pub fn standalone_round_due(&self) -> bool {
+        !self.rounds.contains_key(&RoundKey::standalone()) && self.standalone_bets_placed()
+    }
+
+    /// Why a player can't change their bet to `amount` at `key`, as the event sent back to them.
+    /// Between standalone rounds, this is the bet carried over to the next round.
+    pub fn bet_error(
+        &self,
+        key: &RoundKey,
+        player_id: &Identity,
+        amount: Amount,
+    ) -> Result<Option<ServerEvent>> {
+        if let Some(round) = self.rounds.get(key) {
+            return round.minigame_verifiable.bet_error(player_id, amount);
+        }
+        if key.board.is_some() {
+            return Err(anyhow!("No round in progress for {:?}", key));
+        }
+        let Some(seat) = self.seats.get(player_id) else {
+            return Err(anyhow!("Player is not seated at the table"));
+        };
+        Ok(amount_error(player_id, seat.balance, amount))
+    }
+}
+
+/// Why `amount` can't be bet by a player holding `coins`.
+fn amount_error(player_id: &Identity, coins: Amount, amount: Amount) -> Option<ServerEvent> {
+    if amount > coins {
+        return Some(ServerEvent::InsufficientFunds {
+            player_id: player_id.clone(),
+            available: coins,
+            requested: amount,
+        });
+    }
+    if amount < MIN_BET {
+        return Some(ServerEvent::InvalidBetAmount {
+            min: MIN_BET,
+            max: coins,
+            provided: amount,
+        });
+    }
+    None
+}
+
+impl MinigameInstanceVerifiable {
+    /// Why a player can't change their bet to `amount`, as the event sent back to them.
+    pub fn bet_error(&self, player_id: &Identity, amount: Amount) -> Result<Option<ServerEvent>> {
+        let Some(player) = self.players.get(player_id) else {
+            return Err(anyhow!("Player not found"));
+        };
+        Ok(amount_error(player_id, player.coins, amount))
+    }
+
+    /// Whether a player of the game may advance it, the backend having missed the deadline.
+    fn is_overdue(&self, identity: &Identity, timestamp: u128) -> bool {
+        timestamp > self.deadline && self.players.contains_key(identity)
+    }
+
+    pub fn get_end_results(&self) -> Result<Vec<(Identity, AmountDelta)>> {
+        if self.state != MinigameState::Crashed {
             return Err(anyhow!("Game is still running"));
         }
-        Ok(self.final_results())
+        self.final_results()
     }
 
-    pub fn final_results(&self) -> Vec<(Identity, i32)> {
-        self.minigame_verifiable
-            .players
-            .iter()
-            .map(
-                |(
-                    id,
-                    Player {
-                        bet, cashed_out_at, ..
-                    },
-                )| {
-                    let bet = *bet as f64;
-                    let delta = if let Some(multiplier) = cashed_out_at {
-                        // Player cashed out - calculate profit
-                        (bet * multiplier - bet) as i32
-                    } else {
-                        // Player didn't cash out - lost their bet
-                        -bet as i32
-                    };
-                    (id.clone(), delta)
-                },
-            )
-            .collect::<Vec<_>>()
+    /// The round as kept in the history, once crashed.
+    pub fn summary(&self, key: &RoundKey) -> Result<RoundSummary> {
+        let Some(crash_point) = self.crash_point else {
+            return Err(anyhow!("Game is still running"));
+        };
+        let mut total_wagered: Amount = 0;
+        let mut total_paid: Amount = 0;
+        for player in self.players.values() {
+            total_wagered = amount::checked_add(total_wagered, player.bet)?;
+            if let Some(multiplier) = player.cashed_out_at {
+                total_paid =
+                    amount::checked_add(total_paid, multiplier::winnings(player.bet, multiplier)?)?;
+            }
+        }
+        Ok(RoundSummary {
+            key: key.clone(),
+            crash_point,
+            player_count: self.players.len(),
+            total_wagered,
+            total_paid,
+        })
+    }
+
+    pub fn final_results(&self) -> Result<Vec<(Identity, AmountDelta)>> {
+        let mut results = Vec::new();
+        for (id, player) in &self.players {
+            let payout = if let Some(multiplier) = player.cashed_out_at {
+                // Player cashed out - calculate profit
+                multiplier::winnings(player.bet, multiplier)?
+            } else {
+                // Player didn't cash out - lost their bet
+                0
+            };
+            results.push((id.clone(), amount::delta_between(player.bet, payout)?));
+        }
+        Ok(results)
     }
 }
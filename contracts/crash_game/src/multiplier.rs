@@ -0,0 +1,130 @@
+//! Fixed-point multipliers.
+//!
+//! Everything is integer math, so that the native executor and the zkVM guest
+//! always agree on the curve, cash-outs and winnings.
+
+use anyhow::Result;
+use minigame_interface::{amount, Amount};
+
+/// A multiplier in basis points, 1x being `ONE`.
+pub type Multiplier = u64;
+
+pub const ONE: Multiplier = 10_000;
+/// Multipliers saturate here, far beyond anything a game reaches.
+pub const MAX: Multiplier = 1_000_000 * ONE;
+
+/// Precision of the intermediate `exp` computation.
+const SCALE: u128 = 1_000_000_000_000;
+/// e, at `SCALE`.
+const E: u128 = 2_718_281_828_459;
+/// The curve grows by a factor e every this many milliseconds.
+const E_FOLDING_MS: u64 = 5_000;
+/// Enough Taylor terms for `exp` of a fraction to be exact at `SCALE`.
+const TAYLOR_TERMS: u128 = 20;
+
+/// The multiplier `elapsed_ms` after the start, e^(elapsed / 5s).
+pub fn at(elapsed_ms: u64) -> Multiplier {
+    // e^x = e^n * e^f, with n the integer part of x and f its fraction.
+    let fraction = (elapsed_ms % E_FOLDING_MS) as u128 * SCALE / E_FOLDING_MS as u128;
+    let mut term = SCALE;
+    let mut result = SCALE;
+    for k in 1..=TAYLOR_TERMS {
+        term = term * fraction / SCALE / k;
+        result += term;
+    }
+
+    let max = MAX as u128 * (SCALE / ONE as u128);
+    for _ in 0..elapsed_ms / E_FOLDING_MS {
+        result = result * E / SCALE;
+        if result >= max {
+            return MAX;
+        }
+    }
+    (result / (SCALE / ONE as u128)) as Multiplier
+}
+
+/// How long after the start the curve reaches `multiplier`, the first millisecond where
+/// `at` is at least `multiplier`.
+pub fn reached_at(multiplier: Multiplier) -> u64 {
+    // `at` saturates well before this.
+    let (mut low, mut high) = (0, 100 * E_FOLDING_MS);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if at(mid) >= multiplier {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low
+}
+
+/// What a bet pays back when cashed out at `multiplier`, rounded down.
+pub fn winnings(bet: Amount, multiplier: Multiplier) -> Result<Amount> {
+    Ok(amount::checked_mul(bet, multiplier as Amount)? / ONE as Amount)
+}
+
+/// Formats a multiplier with two decimals, e.g. `1.53`.
+pub fn format(multiplier: Multiplier) -> String {
+    format!("{}.{:02}", multiplier / ONE, multiplier % ONE / 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_one() {
+        assert_eq!(at(0), ONE);
+        assert_eq!(reached_at(ONE), 0);
+    }
+
+    #[test]
+    fn grows_by_e_every_folding_period() {
+        assert_eq!(at(E_FOLDING_MS), 27_182);
+        assert_eq!(at(2 * E_FOLDING_MS), 73_890);
+        assert_eq!(at(E_FOLDING_MS / 2), 16_487);
+    }
+
+    #[test]
+    fn never_decreases() {
+        let mut previous = at(0);
+        for elapsed_ms in 1..=80_000 {
+            let current = at(elapsed_ms);
+            assert!(current >= previous, "at({}) went down", elapsed_ms);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn saturates_at_max() {
+        assert!(at(69_000) < MAX);
+        assert_eq!(at(69_100), MAX);
+        assert_eq!(at(100 * E_FOLDING_MS), MAX);
+        assert_eq!(at(u64::MAX), MAX);
+    }
+
+    #[test]
+    fn reached_at_is_the_first_millisecond_at_the_multiplier() {
+        for multiplier in [ONE + 1, 15_000, 2 * ONE, 27_182, 100 * ONE, MAX] {
+            let t = reached_at(multiplier);
+            assert!(at(t) >= multiplier);
+            assert!(at(t - 1) < multiplier);
+        }
+    }
+
+    #[test]
+    fn winnings_round_down() {
+        assert_eq!(winnings(100, ONE).unwrap(), 100);
+        assert_eq!(winnings(100, 15_000).unwrap(), 150);
+        assert_eq!(winnings(3, 15_000).unwrap(), 4);
+        assert!(winnings(Amount::MAX, 2 * ONE).is_err());
+    }
+
+    #[test]
+    fn formats_two_decimals() {
+        assert_eq!(format(ONE), "1.00");
+        assert_eq!(format(15_300), "1.53");
+        assert_eq!(format(27_182), "2.71");
+    }
+}
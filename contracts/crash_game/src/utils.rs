@@ -1,5 +1,6 @@
 use core::fmt;
 
+use crate::multiplier;
 use crate::ChainEvent;
 
 impl fmt::Display for ChainEvent {
@@ -11,6 +12,23 @@ impl fmt::Display for ChainEvent {
             ChainEvent::GameStarted => {
                 write!(f, "Game started")
             }
+            ChainEvent::PlayerJoined { player_id, balance } => {
+                write!(
+                    f,
+                    "Player {} sat at the table with {} oranj",
+                    player_id, balance
+                )
+            }
+            ChainEvent::PlayerLeft {
+                player_id,
+                withdrawn,
+            } => {
+                write!(
+                    f,
+                    "Player {} left the table with {} oranj",
+                    player_id, withdrawn
+                )
+            }
             ChainEvent::PlayerCashedOut {
                 player_id,
                 multiplier,
@@ -19,11 +37,40 @@ impl fmt::Display for ChainEvent {
                 write!(
                     f,
                     "Player {} cashed out at {}x and won {}",
-                    player_id, multiplier, winnings
+                    player_id,
+                    multiplier::format(*multiplier),
+                    winnings
                 )
             }
+            ChainEvent::AutoCashOutSet {
+                player_id,
+                multiplier,
+            } => match multiplier {
+                Some(multiplier) => {
+                    write!(
+                        f,
+                        "Player {} will cash out at {}x",
+                        player_id,
+                        multiplier::format(*multiplier)
+                    )
+                }
+                None => write!(f, "Player {} cleared their auto cash-out", player_id),
+            },
+            ChainEvent::BetChanged { player_id, amount } => {
+                write!(f, "Player {} changed their bet to {}", player_id, amount)
+            }
             ChainEvent::GameCrashed { final_multiplier } => {
-                write!(f, "Game crashed at {}x", final_multiplier)
+                write!(
+                    f,
+                    "Game crashed at {}x",
+                    multiplier::format(*final_multiplier)
+                )
+            }
+            ChainEvent::RoundVoided => {
+                write!(
+                    f,
+                    "Round voided past its deadline, bets still riding are refunded"
+                )
             }
             ChainEvent::MinigameEnded { final_results } => {
                 write!(
@@ -32,6 +79,13 @@ impl fmt::Display for ChainEvent {
                     final_results.len()
                 )
             }
+            ChainEvent::ChainRotated { generation, length } => {
+                write!(
+                    f,
+                    "Moved to hash chain #{} of {} rounds",
+                    generation, length
+                )
+            }
         }
     }
 }
@@ -0,0 +1,373 @@
+use anyhow::{anyhow, Result};
+use board_game::game::dice::Dice;
+use board_game::GameActionBlob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use minigame_interface::{amount, Amount, AmountDelta, MinigameSetup, TableId};
+use sdk::caller::ExecutionContext;
+use sdk::utils::parse_calldata;
+use sdk::{
+    Blob, BlobData, BlobIndex, Calldata, ContractAction, ContractName, Identity, LaneId, RunResult,
+    StateCommitment, StructuredBlobData, ZkContract,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub mod utils;
+
+/// Rounds are this far apart, so that players can follow the duels.
+pub const ROUND_INTERVAL_MS: u128 = 5_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Duelist {
+    pub id: Identity,
+    pub name: String,
+    // The board bet, staked in the first duel.
+    pub ante: Amount,
+    // What the player holds now: their ante plus the stakes of everyone they beat.
+    pub stake: Amount,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Bracket {
+    pub duelists: BTreeMap<Identity, Duelist>,
+    // Players still in, in bracket order: each round pairs them two by two.
+    pub remaining: Vec<Identity>,
+    pub round: u32,
+    pub next_round_at: u128,
+    pub dice: Dice,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GameState {
+    // One bracket per board table.
+    pub brackets: BTreeMap<TableId, Bracket>,
+    pub board_contract: ContractName,
+    pub backend_identity: Identity,
+    pub last_interaction_time: u128,
+    pub lane_id: LaneId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
+pub enum ChainAction {
+    InitMinigame {
+        table_id: TableId,
+        players: MinigameSetup,
+        // Drawn from the table's dice, the board checks it when starting the minigame.
+        seed: [u8; 32],
+    },
+    PlayRound {
+        table_id: TableId,
+    },
+    Done {
+        table_id: TableId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainEvent {
+    MinigameInitialized {
+        table_id: TableId,
+        bracket: Vec<Identity>,
+    },
+    DuelResolved {
+        table_id: TableId,
+        round: u32,
+        winner: Identity,
+        loser: Identity,
+        // Every throw of the duel, as (winner, loser). Only the last one is not a tie.
+        rolls: Vec<(u8, u8)>,
+        stake: Amount,
+    },
+    ByeGranted {
+        table_id: TableId,
+        round: u32,
+        player_id: Identity,
+    },
+    MinigameEnded {
+        table_id: TableId,
+        final_results: Vec<(Identity, AmountDelta)>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ChainActionBlob(pub u128, pub ChainAction);
+
+impl ContractAction for ChainActionBlob {
+    fn as_blob(
+        &self,
+        contract_name: ContractName,
+        caller: Option<BlobIndex>,
+        callees: Option<Vec<BlobIndex>>,
+    ) -> Blob {
+        Blob {
+            contract_name,
+            data: BlobData::from(StructuredBlobData {
+                caller,
+                callees,
+                parameters: self.clone(),
+            }),
+        }
+    }
+}
+
+impl ZkContract for GameState {
+    fn execute(&mut self, contract_input: &Calldata) -> RunResult {
+        let (action, mut exec_ctx) =
+            parse_calldata::<ChainActionBlob>(contract_input).map_err(|e| e.to_string())?;
+
+        // Not an identity provider
+        if contract_input
+            .identity
+            .0
+            .ends_with(&exec_ctx.contract_name.0)
+        {
+            return Err("Invalid identity provider".to_string());
+        }
+
+        let Some(ref ctx) = contract_input.tx_ctx else {
+            return Err("Missing transaction context".into());
+        };
+
+        // Rollup mode, ensure everything is sent to the same lane ID or we are well past interaction timeout
+        let interaction_timeout = ctx.timestamp.0.saturating_add(60 * 60 * 24 * 1000); // 24 hours
+        if self.lane_id == LaneId::default() || ctx.timestamp.0 > interaction_timeout {
+            self.lane_id = ctx.lane_id.clone();
+        } else if self.lane_id != ctx.lane_id {
+            return Err("Invalid lane ID".into());
+        }
+
+        let events = self
+            .process_chain_action(
+                &contract_input.identity,
+                &action.1,
+                ctx.timestamp.0,
+                Some((&action, &mut exec_ctx)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        self.last_interaction_time = ctx.timestamp.0;
+
+        Ok((borsh::to_vec(&events).unwrap(), exec_ctx, vec![]))
+    }
+
+    fn commit(&self) -> StateCommitment {
+        StateCommitment(borsh::to_vec(self).unwrap())
+    }
+}
+
+impl ChainAction {
+    pub fn table_id(&self) -> TableId {
+        match self {
+            ChainAction::InitMinigame { table_id, .. }
+            | ChainAction::PlayRound { table_id }
+            | ChainAction::Done { table_id } => *table_id,
+        }
+    }
+}
+
+impl Bracket {
+    /// Seeds the bracket by shuffling the players with the dice.
+    pub fn new(players: &MinigameSetup, seed: [u8; 32], timestamp: u128) -> Self {
+        let mut dice = Dice::new(1, 6, seed);
+        let mut remaining: Vec<Identity> = players.iter().map(|(id, ..)| id.clone()).collect();
+        dice.shuffle(&mut remaining);
+        let duelists = players
+            .iter()
+            .map(|(id, name, ante, _)| {
+                (
+                    id.clone(),
+                    Duelist {
+                        id: id.clone(),
+                        name: name.clone(),
+                        ante: *ante,
+                        stake: *ante,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            duelists,
+            remaining,
+            round: 0,
+            next_round_at: timestamp.saturating_add(ROUND_INTERVAL_MS),
+            dice,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining.len() <= 1
+    }
+
+    /// Pairs the remaining players two by two, the last one getting a bye when they are odd.
+    /// Both players of a duel roll until one is higher, and the loser's stake goes to the winner.
+    pub fn play_round(&mut self, table_id: TableId) -> Result<Vec<ChainEvent>> {
+        let mut events = Vec::new();
+        let remaining = std::mem::take(&mut self.remaining);
+        for pair in remaining.chunks(2) {
+            let [first, second] = pair else {
+                events.push(ChainEvent::ByeGranted {
+                    table_id,
+                    round: self.round,
+                    player_id: pair[0].clone(),
+                });
+                self.remaining.push(pair[0].clone());
+                continue;
+            };
+
+            let mut rolls = Vec::new();
+            let (winner, loser) = loop {
+                let (a, b) = (self.dice.roll(), self.dice.roll());
+                if a != b {
+                    rolls.push((a.max(b), a.min(b)));
+                    break if a > b {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                }
+                rolls.push((a, b));
+            };
+
+            let stake = self.take_stake(loser)?;
+            let duelist = self
+                .duelists
+                .get_mut(winner)
+                .ok_or_else(|| anyhow!("Player not found"))?;
+            duelist.stake = amount::checked_add(duelist.stake, stake)?;
+
+            events.push(ChainEvent::DuelResolved {
+                table_id,
+                round: self.round,
+                winner: winner.clone(),
+                loser: loser.clone(),
+                rolls,
+                stake,
+            });
+            self.remaining.push(winner.clone());
+        }
+        self.round += 1;
+        Ok(events)
+    }
+
+    fn take_stake(&mut self, player_id: &Identity) -> Result<Amount> {
+        let duelist = self
+            .duelists
+            .get_mut(player_id)
+            .ok_or_else(|| anyhow!("Player not found"))?;
+        Ok(std::mem::take(&mut duelist.stake))
+    }
+
+    pub fn final_results(&self) -> Result<Vec<(Identity, AmountDelta)>> {
+        self.duelists
+            .iter()
+            .map(|(id, duelist)| {
+                Ok((
+                    id.clone(),
+                    amount::delta_between(duelist.ante, duelist.stake)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl GameState {
+    pub fn new(board_contract: ContractName, backend_identity: Identity) -> Self {
+        Self {
+            brackets: BTreeMap::new(),
+            board_contract,
+            backend_identity,
+            last_interaction_time: 0,
+            lane_id: LaneId::default(),
+        }
+    }
+
+    pub fn process_chain_action(
+        &mut self,
+        _identity: &Identity,
+        action: &ChainAction,
+        timestamp: u128,
+        ctx: Option<(&ChainActionBlob, &mut ExecutionContext)>,
+    ) -> Result<Vec<ChainEvent>> {
+        let mut events = Vec::new();
+
+        match action {
+            ChainAction::InitMinigame {
+                table_id,
+                players,
+                seed,
+            } => {
+                if self.brackets.contains_key(table_id) {
+                    return Err(anyhow!(
+                        "A bracket is already running at table {}",
+                        table_id
+                    ));
+                }
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // Check our data matches the board contract
+                    minigame_interface::verify_board_start::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        players,
+                        Some(*seed),
+                    )?;
+                }
+
+                let bracket = Bracket::new(players, *seed, timestamp);
+                events.push(ChainEvent::MinigameInitialized {
+                    table_id: *table_id,
+                    bracket: bracket.remaining.clone(),
+                });
+                self.brackets.insert(*table_id, bracket);
+            }
+
+            // Anyone may play a round once it is due: the outcome only depends on the seeded dice.
+            ChainAction::PlayRound { table_id } => {
+                let bracket = self.get_bracket_mut(*table_id)?;
+                if bracket.is_over() {
+                    return Err(anyhow!("The bracket is already decided"));
+                }
+                if timestamp < bracket.next_round_at {
+                    return Err(anyhow!("The next round is not due yet"));
+                }
+                events.extend(bracket.play_round(*table_id)?);
+                bracket.next_round_at = timestamp.saturating_add(ROUND_INTERVAL_MS);
+            }
+
+            ChainAction::Done { table_id } => {
+                let bracket = self.get_bracket_mut(*table_id)?;
+                if !bracket.is_over() {
+                    return Err(anyhow!("Cannot end the minigame before the final duel"));
+                }
+                let expected_final_results = bracket.final_results()?;
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // When ending the minigame, verify that the board game is being updated with the correct data
+                    minigame_interface::verify_board_end::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        &expected_final_results,
+                    )?;
+                }
+
+                self.brackets.remove(table_id);
+                events.push(ChainEvent::MinigameEnded {
+                    table_id: *table_id,
+                    final_results: expected_final_results,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn get_bracket_mut(&mut self, table_id: TableId) -> Result<&mut Bracket> {
+        self.brackets
+            .get_mut(&table_id)
+            .ok_or_else(|| anyhow!("No bracket running at table {}", table_id))
+    }
+}
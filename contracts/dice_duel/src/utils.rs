@@ -0,0 +1,59 @@
+use core::fmt;
+
+use crate::ChainEvent;
+
+impl fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEvent::MinigameInitialized { table_id, bracket } => {
+                write!(
+                    f,
+                    "Dice duel bracket initialized at table {} with {} players",
+                    table_id,
+                    bracket.len()
+                )
+            }
+            ChainEvent::DuelResolved {
+                table_id,
+                round,
+                winner,
+                loser,
+                rolls,
+                stake,
+            } => {
+                write!(
+                    f,
+                    "Player {} beat {} in {} throws and took {} at table {}, round {}",
+                    winner,
+                    loser,
+                    rolls.len(),
+                    stake,
+                    table_id,
+                    round
+                )
+            }
+            ChainEvent::ByeGranted {
+                table_id,
+                round,
+                player_id,
+            } => {
+                write!(
+                    f,
+                    "Player {} has a bye at table {}, round {}",
+                    player_id, table_id, round
+                )
+            }
+            ChainEvent::MinigameEnded {
+                table_id,
+                final_results,
+            } => {
+                write!(
+                    f,
+                    "Dice duel ended at table {} with {} player results",
+                    table_id,
+                    final_results.len()
+                )
+            }
+        }
+    }
+}
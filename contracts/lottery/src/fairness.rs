@@ -0,0 +1,32 @@
+//! Verifiable draws.
+//!
+//! Like the crash game, the backend commits to the end of a hash chain when the contract is
+//! registered, then reveals it backwards, one pre-image per draw. Winning tickets are picked
+//! from the pre-image, so nobody can choose them once tickets are sold. Each draw claims its
+//! element when tickets are sold, and draws are settled in that order.
+
+use sha2::{Digest, Sha256};
+
+use minigame_interface::TableId;
+
+pub fn hash(seed: &[u8; 32]) -> [u8; 32] {
+    Sha256::digest(seed).into()
+}
+
+/// Whether `seed` is the pre-image of the current chain `head`.
+pub fn is_preimage(seed: &[u8; 32], head: &[u8; 32]) -> bool {
+    hash(seed) == *head
+}
+
+/// The element `length` hashes away from `seed`, committed to as the chain head.
+pub fn chain_end(seed: [u8; 32], length: u32) -> [u8; 32] {
+    (0..length).fold(seed, |current, _| hash(&current))
+}
+
+/// The dice seed of a draw, specific to the table it is revealed for.
+pub fn draw_seed(seed: &[u8; 32], table_id: TableId) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(table_id.to_le_bytes());
+    hasher.finalize().into()
+}
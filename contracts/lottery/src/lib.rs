@@ -0,0 +1,456 @@
+use anyhow::{anyhow, Result};
+use board_game::game::dice::Dice;
+use board_game::GameActionBlob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use minigame_interface::{amount, Amount, AmountDelta, MinigameSetup, TableId};
+use sdk::caller::ExecutionContext;
+use sdk::utils::parse_calldata;
+use sdk::{
+    Blob, BlobData, BlobIndex, Calldata, ContractAction, ContractName, Identity, LaneId, RunResult,
+    StateCommitment, StructuredBlobData, ZkContract,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub mod fairness;
+pub mod utils;
+
+/// Tickets are on display this long before the draw.
+pub const DRAW_DELAY_MS: u128 = 15_000;
+/// Percentage of each pot rolled over into the jackpot.
+pub const JACKPOT_SHARE_PERCENT: Amount = 10;
+/// One draw in this many also pays out the jackpot.
+pub const JACKPOT_ODDS: u64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TicketHolder {
+    pub id: Identity,
+    pub name: String,
+    // The board bet. Each coin staked is one ticket.
+    pub stake: Amount,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Draw {
+    pub holders: BTreeMap<Identity, TicketHolder>,
+    pub draw_at: u128,
+    // Position of the draw's seed in the backend's hash chain, claimed when tickets are sold.
+    pub seed_index: u64,
+}
+
+/// The outcome of a draw, computed identically by the contract and the backend.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Settlement {
+    pub pot_winner: Option<Identity>,
+    pub prize: Amount,
+    pub jackpot_winner: Option<Identity>,
+    pub jackpot_won: Amount,
+    // The jackpot once this draw is settled.
+    pub jackpot: Amount,
+    pub final_results: Vec<(Identity, AmountDelta)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GameState {
+    // One draw per board table.
+    pub draws: BTreeMap<TableId, Draw>,
+    // Carried over from draw to draw, across board games, until someone wins it.
+    pub jackpot: Amount,
+    // The last revealed element of the backend's hash chain, see `fairness`.
+    pub chain_head: [u8; 32],
+    // How many chain elements were revealed, and claimed by draws.
+    pub chain_revealed: u64,
+    pub chain_claimed: u64,
+    // How many elements the current chain has, past which no draw can be set up.
+    pub chain_length: u32,
+    // How many times the backend moved to a new chain, once the previous one ran low.
+    pub chain_generation: u32,
+    pub board_contract: ContractName,
+    pub backend_identity: Identity,
+    pub last_interaction_time: u128,
+    pub lane_id: LaneId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainAction {
+    InitMinigame {
+        table_id: TableId,
+        players: MinigameSetup,
+    },
+    /// Draws with the chain element claimed by the draw.
+    Done { table_id: TableId, seed: [u8; 32] },
+    /// Commits to a new hash chain once the current one runs low, while no draw is running.
+    RotateChain { head: [u8; 32], length: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainEvent {
+    MinigameInitialized {
+        table_id: TableId,
+        tickets: Amount,
+    },
+    Drawn {
+        table_id: TableId,
+        pot_winner: Option<Identity>,
+        prize: Amount,
+        jackpot_winner: Option<Identity>,
+        jackpot_won: Amount,
+        jackpot: Amount,
+    },
+    MinigameEnded {
+        table_id: TableId,
+        final_results: Vec<(Identity, AmountDelta)>,
+    },
+    ChainRotated {
+        generation: u32,
+        length: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ChainActionBlob(pub u128, pub ChainAction);
+
+impl ContractAction for ChainActionBlob {
+    fn as_blob(
+        &self,
+        contract_name: ContractName,
+        caller: Option<BlobIndex>,
+        callees: Option<Vec<BlobIndex>>,
+    ) -> Blob {
+        Blob {
+            contract_name,
+            data: BlobData::from(StructuredBlobData {
+                caller,
+                callees,
+                parameters: self.clone(),
+            }),
+        }
+    }
+}
+
+impl ZkContract for GameState {
+    fn execute(&mut self, contract_input: &Calldata) -> RunResult {
+        let (action, mut exec_ctx) =
+            parse_calldata::<ChainActionBlob>(contract_input).map_err(|e| e.to_string())?;
+
+        // Not an identity provider
+        if contract_input
+            .identity
+            .0
+            .ends_with(&exec_ctx.contract_name.0)
+        {
+            return Err("Invalid identity provider".to_string());
+        }
+
+        let Some(ref ctx) = contract_input.tx_ctx else {
+            return Err("Missing transaction context".into());
+        };
+
+        // Rollup mode, ensure everything is sent to the same lane ID or we are well past interaction timeout
+        let interaction_timeout = ctx.timestamp.0.saturating_add(60 * 60 * 24 * 1000); // 24 hours
+        if self.lane_id == LaneId::default() || ctx.timestamp.0 > interaction_timeout {
+            self.lane_id = ctx.lane_id.clone();
+        } else if self.lane_id != ctx.lane_id {
+            return Err("Invalid lane ID".into());
+        }
+
+        let events = self
+            .process_chain_action(
+                &contract_input.identity,
+                &action.1,
+                ctx.timestamp.0,
+                Some((&action, &mut exec_ctx)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        self.last_interaction_time = ctx.timestamp.0;
+
+        Ok((borsh::to_vec(&events).unwrap(), exec_ctx, vec![]))
+    }
+
+    fn commit(&self) -> StateCommitment {
+        StateCommitment(borsh::to_vec(self).unwrap())
+    }
+}
+
+impl ChainAction {
+    pub fn table_id(&self) -> Option<TableId> {
+        match self {
+            ChainAction::InitMinigame { table_id, .. } | ChainAction::Done { table_id, .. } => {
+                Some(*table_id)
+            }
+            ChainAction::RotateChain { .. } => None,
+        }
+    }
+}
+
+impl Draw {
+    pub fn tickets(&self) -> Result<Amount> {
+        self.holders
+            .values()
+            .try_fold(0, |total, h| amount::checked_add(total, h.stake))
+    }
+
+    /// The holder of a ticket picked uniformly among all `tickets`.
+    fn pick(&self, dice: &mut Dice, tickets: Amount) -> Result<Identity> {
+        let tickets = u64::try_from(tickets).map_err(|_| anyhow!("Too many tickets"))?;
+        let mut ticket = dice.range(tickets) as Amount;
+        for (id, holder) in &self.holders {
+            if ticket < holder.stake {
+                return Ok(id.clone());
+            }
+            ticket -= holder.stake;
+        }
+        Err(anyhow!("Ticket out of range"))
+    }
+
+    /// Every stake goes into the pot, minus `JACKPOT_SHARE_PERCENT` rolled over into the jackpot.
+    /// One ticket wins the pot; one draw in `JACKPOT_ODDS`, another ticket also wins the jackpot.
+    pub fn settle(
+        &self,
+        table_id: TableId,
+        seed: &[u8; 32],
+        jackpot: Amount,
+    ) -> Result<Settlement> {
+        let pot = self.tickets()?;
+        if pot == 0 {
+            return Ok(self.refund(jackpot));
+        }
+        let mut deltas = BTreeMap::new();
+        for (id, holder) in &self.holders {
+            deltas.insert(id.clone(), -amount::to_delta(holder.stake)?);
+        }
+
+        let mut dice = Dice::new(1, 6, fairness::draw_seed(seed, table_id));
+        let cut = amount::checked_mul(pot, JACKPOT_SHARE_PERCENT)? / 100;
+        let prize = pot - cut;
+        let mut jackpot = amount::checked_add(jackpot, cut)?;
+
+        let pot_winner = self.pick(&mut dice, pot)?;
+        credit(&mut deltas, &pot_winner, prize)?;
+
+        let (jackpot_winner, jackpot_won) = if dice.range(JACKPOT_ODDS) == 0 {
+            let winner = self.pick(&mut dice, pot)?;
+            credit(&mut deltas, &winner, jackpot)?;
+            (Some(winner), std::mem::take(&mut jackpot))
+        } else {
+            (None, 0)
+        };
+
+        Ok(Settlement {
+            pot_winner: Some(pot_winner),
+            prize,
+            jackpot_winner,
+            jackpot_won,
+            jackpot,
+            final_results: deltas.into_iter().collect(),
+        })
+    }
+
+    /// Nobody wins or loses anything, and the jackpot is untouched.
+    pub fn refund(&self, jackpot: Amount) -> Settlement {
+        Settlement {
+            pot_winner: None,
+            prize: 0,
+            jackpot_winner: None,
+            jackpot_won: 0,
+            jackpot,
+            final_results: self.holders.keys().map(|id| (id.clone(), 0)).collect(),
+        }
+    }
+}
+
+fn credit(
+    deltas: &mut BTreeMap<Identity, AmountDelta>,
+    player_id: &Identity,
+    coins: Amount,
+) -> Result<()> {
+    let delta = deltas
+        .get_mut(player_id)
+        .ok_or_else(|| anyhow!("Player not found"))?;
+    *delta = delta
+        .checked_add(amount::to_delta(coins)?)
+        .ok_or_else(|| anyhow!("Delta overflow"))?;
+    Ok(())
+}
+
+impl GameState {
+    pub fn new(
+        board_contract: ContractName,
+        backend_identity: Identity,
+        chain_head: [u8; 32],
+        chain_length: u32,
+    ) -> Self {
+        Self {
+            draws: BTreeMap::new(),
+            jackpot: 0,
+            chain_head,
+            chain_revealed: 0,
+            chain_claimed: 0,
+            chain_length,
+            chain_generation: 0,
+            board_contract,
+            backend_identity,
+            last_interaction_time: 0,
+            lane_id: LaneId::default(),
+        }
+    }
+
+    /// Checks that `seed` is the chain element at `seed_index`. Revealing it also reveals
+    /// every earlier element, so the draws that claimed them must be settled first.
+    fn check_seed(&self, seed_index: u64, seed: &[u8; 32]) -> Result<()> {
+        if !self.can_draw(seed_index) {
+            return Err(anyhow!("Draws set up earlier have to be drawn first"));
+        }
+        let depth = seed_index
+            .checked_sub(self.chain_revealed)
+            .filter(|depth| *depth > 0)
+            .ok_or_else(|| anyhow!("The seed of this draw was already revealed"))?;
+        let depth = u32::try_from(depth).map_err(|_| anyhow!("Seed index out of range"))?;
+        if fairness::chain_end(*seed, depth) != self.chain_head {
+            return Err(anyhow!("Seed does not match the committed hash chain"));
+        }
+        Ok(())
+    }
+
+    /// Whether the draw at `seed_index` comes first, draws being settled in the order they claimed seeds.
+    pub fn can_draw(&self, seed_index: u64) -> bool {
+        self.draws
+            .values()
+            .all(|draw| draw.seed_index >= seed_index)
+    }
+
+    pub fn process_chain_action(
+        &mut self,
+        identity: &Identity,
+        action: &ChainAction,
+        timestamp: u128,
+        ctx: Option<(&ChainActionBlob, &mut ExecutionContext)>,
+    ) -> Result<Vec<ChainEvent>> {
+        let mut events = Vec::new();
+
+        match action {
+            ChainAction::InitMinigame { table_id, players } => {
+                if self.draws.contains_key(table_id) {
+                    return Err(anyhow!("A draw is already running at table {}", table_id));
+                }
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // Check our data matches the board contract
+                    minigame_interface::verify_board_start::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        players,
+                        None,
+                    )?;
+                }
+
+                let holders = players
+                    .iter()
+                    .map(|(id, name, stake, _)| {
+                        (
+                            id.clone(),
+                            TicketHolder {
+                                id: id.clone(),
+                                name: name.clone(),
+                                stake: *stake,
+                            },
+                        )
+                    })
+                    .collect();
+                // The seed is claimed now, so the backend never picks which draw gets which one.
+                self.chain_claimed = self
+                    .chain_claimed
+                    .checked_add(1)
+                    .filter(|index| *index <= self.chain_length as u64)
+                    .ok_or_else(|| {
+                        anyhow!("Hash chain exhausted, waiting for the backend to rotate it")
+                    })?;
+                let draw = Draw {
+                    holders,
+                    draw_at: timestamp.saturating_add(DRAW_DELAY_MS),
+                    seed_index: self.chain_claimed,
+                };
+                events.push(ChainEvent::MinigameInitialized {
+                    table_id: *table_id,
+                    tickets: draw.tickets()?,
+                });
+                self.draws.insert(*table_id, draw);
+            }
+
+            ChainAction::Done { table_id, seed } => {
+                let Some(draw) = self.draws.get(table_id) else {
+                    return Err(anyhow!("No draw running at table {}", table_id));
+                };
+                if timestamp < draw.draw_at {
+                    return Err(anyhow!("Tickets are still on display"));
+                }
+                let seed_index = draw.seed_index;
+                self.check_seed(seed_index, seed)?;
+                let settlement = draw.settle(*table_id, seed, self.jackpot)?;
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // When ending the minigame, verify that the board game is being updated with the correct data
+                    minigame_interface::verify_board_end::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        &settlement.final_results,
+                    )?;
+                }
+
+                self.chain_head = *seed;
+                self.chain_revealed = seed_index;
+                events.push(ChainEvent::Drawn {
+                    table_id: *table_id,
+                    pot_winner: settlement.pot_winner,
+                    prize: settlement.prize,
+                    jackpot_winner: settlement.jackpot_winner,
+                    jackpot_won: settlement.jackpot_won,
+                    jackpot: settlement.jackpot,
+                });
+                self.jackpot = settlement.jackpot;
+                self.draws.remove(table_id);
+                events.push(ChainEvent::MinigameEnded {
+                    table_id: *table_id,
+                    final_results: settlement.final_results,
+                });
+            }
+
+            ChainAction::RotateChain { head, length } => {
+                if identity != &self.backend_identity {
+                    return Err(anyhow!(
+                        "Only the backend can rotate the hash chain: {} vs {}",
+                        identity,
+                        self.backend_identity
+                    ));
+                }
+                if *length == 0 {
+                    return Err(anyhow!("The new hash chain cannot be empty"));
+                }
+                // Running draws claimed elements of the current chain.
+                if !self.draws.is_empty() {
+                    return Err(anyhow!("Draws are still running"));
+                }
+
+                self.chain_generation = self
+                    .chain_generation
+                    .checked_add(1)
+                    .ok_or_else(|| anyhow!("Too many hash chains"))?;
+                self.chain_head = *head;
+                self.chain_revealed = 0;
+                self.chain_claimed = 0;
+                self.chain_length = *length;
+                events.push(ChainEvent::ChainRotated {
+                    generation: self.chain_generation,
+                    length: *length,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+}
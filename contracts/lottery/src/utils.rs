@@ -0,0 +1,52 @@
+use core::fmt;
+
+use crate::ChainEvent;
+
+impl fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEvent::MinigameInitialized { table_id, tickets } => {
+                write!(
+                    f,
+                    "Lottery initialized at table {} with {} tickets",
+                    table_id, tickets
+                )
+            }
+            ChainEvent::Drawn {
+                table_id,
+                pot_winner,
+                prize,
+                jackpot_winner,
+                jackpot_won,
+                jackpot,
+            } => {
+                match pot_winner {
+                    Some(winner) => {
+                        write!(f, "Player {} won {} at table {}", winner, prize, table_id)?
+                    }
+                    None => write!(f, "No tickets were drawn at table {}", table_id)?,
+                }
+                match jackpot_winner {
+                    Some(winner) => {
+                        write!(f, ", player {} won the jackpot of {}", winner, jackpot_won)
+                    }
+                    None => write!(f, ", the jackpot is now {}", jackpot),
+                }
+            }
+            ChainEvent::MinigameEnded {
+                table_id,
+                final_results,
+            } => {
+                write!(
+                    f,
+                    "Lottery ended at table {} with {} player results",
+                    table_id,
+                    final_results.len()
+                )
+            }
+            ChainEvent::ChainRotated { generation, length } => {
+                write!(f, "Moved to hash chain #{} of {} draws", generation, length)
+            }
+        }
+    }
+}
@@ -2,6 +2,11 @@ mod metadata {
     //use sp1_sdk::include_elf;
     pub const BOARD_GAME_ELF: &[u8] = include_bytes!("../elf/board_game"); //include_elf!("board_game");
     pub const CRASH_GAME_ELF: &[u8] = include_bytes!("../elf/crash_game"); //include_elf!("crash_game");
+    pub const AUCTION_GAME_ELF: &[u8] = include_bytes!("../elf/auction_game"); //include_elf!("auction_game");
+    pub const DICE_DUEL_ELF: &[u8] = include_bytes!("../elf/dice_duel"); //include_elf!("dice_duel");
+    pub const RESOURCE_SPLIT_ELF: &[u8] = include_bytes!("../elf/resource_split");
+    pub const LOTTERY_ELF: &[u8] = include_bytes!("../elf/lottery");
+    //include_elf!("resource_split");
 }
 
 pub use metadata::*;
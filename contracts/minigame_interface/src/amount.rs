@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Result};
+
+/// Coins, bets and deposits, in the same unit and width as the token transfers backing them.
+pub type Amount = u128;
+/// A signed change of an [`Amount`], as reported by minigames.
+pub type AmountDelta = i128;
+
+pub fn checked_add(a: Amount, b: Amount) -> Result<Amount> {
+    a.checked_add(b)
+        .ok_or_else(|| anyhow!("Amount overflow: {} + {}", a, b))
+}
+
+pub fn checked_sub(a: Amount, b: Amount) -> Result<Amount> {
+    a.checked_sub(b)
+        .ok_or_else(|| anyhow!("Amount underflow: {} - {}", a, b))
+}
+
+pub fn checked_mul(a: Amount, b: Amount) -> Result<Amount> {
+    a.checked_mul(b)
+        .ok_or_else(|| anyhow!("Amount overflow: {} * {}", a, b))
+}
+
+pub fn to_delta(amount: Amount) -> Result<AmountDelta> {
+    AmountDelta::try_from(amount).map_err(|_| anyhow!("Amount {} is too large", amount))
+}
+
+/// The difference `after - before`, as a signed delta.
+pub fn delta_between(before: Amount, after: Amount) -> Result<AmountDelta> {
+    if after >= before {
+        to_delta(after - before)
+    } else {
+        Ok(-to_delta(before - after)?)
+    }
+}
@@ -0,0 +1,225 @@
+//! How minigame contracts compose with the board game.
+//!
+//! A minigame is started and ended in the same transaction as a board action:
+//! - the board checks that the action is sent by the minigame contract itself (`verify_caller`),
+//! - the minigame checks that the board action carries the same data it does
+//!   (`verify_board_start` and `verify_board_end`).
+
+use anyhow::{anyhow, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use sdk::caller::ExecutionContext;
+use sdk::{ContractName, Identity};
+use serde::{Deserialize, Serialize};
+
+pub mod amount;
+
+pub use amount::{Amount, AmountDelta};
+
+pub type TableId = u32;
+
+/// Players taking part in a minigame, with their name, their bet and their coins on the board.
+pub type MinigameSetup = Vec<(Identity, String, Amount, Amount)>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct MinigameResult {
+    pub contract_name: ContractName,
+    pub player_results: Vec<PlayerMinigameResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct PlayerMinigameResult {
+    pub player_id: Identity,
+    pub coins_delta: AmountDelta,
+}
+
+impl MinigameResult {
+    pub fn new(contract_name: ContractName, results: &[(Identity, AmountDelta)]) -> Self {
+        Self {
+            contract_name,
+            player_results: results
+                .iter()
+                .map(|(player_id, coins_delta)| PlayerMinigameResult {
+                    player_id: player_id.clone(),
+                    coins_delta: *coins_delta,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The board side of the composition: the blob a board contract expects next to a minigame's.
+pub trait BoardProtocol:
+    BorshSerialize + BorshDeserialize + PartialEq + Clone + std::fmt::Debug
+{
+    fn start_minigame(
+        uuid: u128,
+        table_id: TableId,
+        minigame: ContractName,
+        players: MinigameSetup,
+        seed: Option<[u8; 32]>,
+    ) -> Self;
+
+    fn end_minigame(uuid: u128, table_id: TableId, result: MinigameResult) -> Self;
+}
+
+/// Board side: minigame actions must be sent by the minigame contract itself.
+pub fn verify_caller(exec_ctx: &ExecutionContext, minigame: &ContractName) -> Result<()> {
+    if exec_ctx.caller.0 != minigame.0 {
+        return Err(anyhow!(
+            "Invalid caller {} for minigame {}",
+            exec_ctx.caller,
+            minigame
+        ));
+    }
+    Ok(())
+}
+
+/// Minigame side: the board must start this minigame, at this table, with these players.
+/// Minigames seeded from the board pass their seed, which the board checks against its dice.
+pub fn verify_board_start<B: BoardProtocol>(
+    exec_ctx: &ExecutionContext,
+    board_contract: &ContractName,
+    uuid: u128,
+    table_id: TableId,
+    players: &MinigameSetup,
+    seed: Option<[u8; 32]>,
+) -> Result<()> {
+    let expected = B::start_minigame(
+        uuid,
+        table_id,
+        exec_ctx.contract_name.clone(),
+        players.clone(),
+        seed,
+    );
+    exec_ctx
+        .is_in_callee_blobs(board_contract, expected)
+        .map_err(|_| anyhow!("Missing or incorrect board game StartMinigame action in transaction"))
+}
+
+/// Minigame side: the board must apply exactly these results.
+pub fn verify_board_end<B: BoardProtocol>(
+    exec_ctx: &ExecutionContext,
+    board_contract: &ContractName,
+    uuid: u128,
+    table_id: TableId,
+    results: &[(Identity, AmountDelta)],
+) -> Result<()> {
+    let expected = B::end_minigame(
+        uuid,
+        table_id,
+        MinigameResult::new(exec_ctx.contract_name.clone(), results),
+    );
+    exec_ctx
+        .is_in_callee_blobs(board_contract, expected.clone())
+        .map_err(|_| {
+            anyhow!(
+                "Missing board game EndMinigame action in transaction, expected: {:?}",
+                expected
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk::{Blob, BlobData, StructuredBlobData};
+
+    #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq)]
+    enum TestBoardAction {
+        Start(u128, TableId, ContractName, MinigameSetup, Option<[u8; 32]>),
+        End(u128, TableId, MinigameResult),
+    }
+
+    impl BoardProtocol for TestBoardAction {
+        fn start_minigame(
+            uuid: u128,
+            table_id: TableId,
+            minigame: ContractName,
+            players: MinigameSetup,
+            seed: Option<[u8; 32]>,
+        ) -> Self {
+            Self::Start(uuid, table_id, minigame, players, seed)
+        }
+
+        fn end_minigame(uuid: u128, table_id: TableId, result: MinigameResult) -> Self {
+            Self::End(uuid, table_id, result)
+        }
+    }
+
+    fn board_blob(contract_name: &str, action: TestBoardAction) -> Blob {
+        Blob {
+            contract_name: ContractName::new(contract_name),
+            data: BlobData::from(StructuredBlobData {
+                caller: None,
+                callees: None,
+                parameters: action,
+            }),
+        }
+    }
+
+    fn exec_ctx(callees_blobs: Vec<Blob>) -> ExecutionContext {
+        ExecutionContext {
+            callees_blobs,
+            caller: Identity::new("minigame"),
+            contract_name: ContractName::new("minigame"),
+        }
+    }
+
+    fn players() -> MinigameSetup {
+        vec![(Identity::new("alice"), "Alice".to_string(), 10, 100)]
+    }
+
+    fn results() -> Vec<(Identity, AmountDelta)> {
+        vec![(Identity::new("alice"), -10)]
+    }
+
+    fn start(table_id: TableId) -> TestBoardAction {
+        TestBoardAction::start_minigame(1, table_id, ContractName::new("minigame"), players(), None)
+    }
+
+    fn end(table_id: TableId) -> TestBoardAction {
+        TestBoardAction::end_minigame(
+            1,
+            table_id,
+            MinigameResult::new(ContractName::new("minigame"), &results()),
+        )
+    }
+
+    #[test]
+    fn caller_must_be_the_minigame() {
+        let ctx = exec_ctx(vec![]);
+        assert!(verify_caller(&ctx, &ContractName::new("minigame")).is_ok());
+        assert!(verify_caller(&ctx, &ContractName::new("other")).is_err());
+    }
+
+    #[test]
+    fn board_start_is_checked() {
+        let board = ContractName::new("board_game");
+        let check = |ctx: &ExecutionContext| {
+            verify_board_start::<TestBoardAction>(ctx, &board, 1, 7, &players(), None)
+        };
+
+        assert!(check(&exec_ctx(vec![board_blob("board_game", start(7))])).is_ok());
+        // No board blob at all.
+        assert!(check(&exec_ctx(vec![])).is_err());
+        // Right action, wrong board contract.
+        assert!(check(&exec_ctx(vec![board_blob("other_board", start(7))])).is_err());
+        // Right board, another table.
+        assert!(check(&exec_ctx(vec![board_blob("board_game", start(8))])).is_err());
+    }
+
+    #[test]
+    fn board_end_is_checked() {
+        let board = ContractName::new("board_game");
+        let check = |ctx: &ExecutionContext| {
+            verify_board_end::<TestBoardAction>(ctx, &board, 1, 7, &results())
+        };
+
+        assert!(check(&exec_ctx(vec![board_blob("board_game", end(7))])).is_ok());
+        assert!(check(&exec_ctx(vec![])).is_err());
+        assert!(check(&exec_ctx(vec![board_blob("other_board", end(7))])).is_err());
+        assert!(check(&exec_ctx(vec![board_blob("board_game", end(8))])).is_err());
+        // A start action does not stand in for the end.
+        assert!(check(&exec_ctx(vec![board_blob("board_game", start(7))])).is_err());
+    }
+}
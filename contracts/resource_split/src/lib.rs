@@ -0,0 +1,418 @@
+use anyhow::{anyhow, Result};
+use board_game::GameActionBlob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use minigame_interface::{amount, Amount, AmountDelta, MinigameSetup, TableId};
+use sdk::caller::ExecutionContext;
+use sdk::utils::parse_calldata;
+use sdk::{
+    Blob, BlobData, BlobIndex, Calldata, ContractAction, ContractName, Identity, LaneId, RunResult,
+    StateCommitment, StructuredBlobData, ZkContract,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+pub mod utils;
+
+/// Players have this long to commit their choice.
+pub const COMMIT_WINDOW_MS: u128 = 30_000;
+/// Once choices are committed, players have this long to reveal them.
+pub const REVEAL_WINDOW_MS: u128 = 30_000;
+/// Shared resources are worth this many times the pot.
+pub const RESOURCE_MULTIPLIER: Amount = 2;
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq,
+)]
+pub enum Choice {
+    Share,
+    Hoard,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Colonist {
+    pub id: Identity,
+    pub name: String,
+    // The board bet, paid into the resource pot.
+    pub ante: Amount,
+    pub commitment: Option<[u8; 32]>,
+    pub choice: Option<Choice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Split {
+    pub colonists: BTreeMap<Identity, Colonist>,
+    pub commit_deadline: u128,
+    pub reveal_deadline: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct GameState {
+    // One split per board table.
+    pub splits: BTreeMap<TableId, Split>,
+    pub board_contract: ContractName,
+    pub backend_identity: Identity,
+    pub last_interaction_time: u128,
+    pub lane_id: LaneId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainAction {
+    InitMinigame {
+        table_id: TableId,
+        players: MinigameSetup,
+    },
+    Commit {
+        table_id: TableId,
+        player_id: Identity,
+        // See `choice_commitment`.
+        commitment: [u8; 32],
+    },
+    Reveal {
+        table_id: TableId,
+        player_id: Identity,
+        choice: Choice,
+        salt: [u8; 32],
+    },
+    Done {
+        table_id: TableId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainEvent {
+    MinigameInitialized {
+        table_id: TableId,
+        player_count: usize,
+    },
+    ChoiceCommitted {
+        table_id: TableId,
+        player_id: Identity,
+    },
+    ChoiceRevealed {
+        table_id: TableId,
+        player_id: Identity,
+        choice: Choice,
+    },
+    ResourcesSplit {
+        table_id: TableId,
+        sharers: usize,
+        hoarders: usize,
+        pot: Amount,
+    },
+    MinigameEnded {
+        table_id: TableId,
+        final_results: Vec<(Identity, AmountDelta)>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ChainActionBlob(pub u128, pub ChainAction);
+
+impl ContractAction for ChainActionBlob {
+    fn as_blob(
+        &self,
+        contract_name: ContractName,
+        caller: Option<BlobIndex>,
+        callees: Option<Vec<BlobIndex>>,
+    ) -> Blob {
+        Blob {
+            contract_name,
+            data: BlobData::from(StructuredBlobData {
+                caller,
+                callees,
+                parameters: self.clone(),
+            }),
+        }
+    }
+}
+
+impl ZkContract for GameState {
+    fn execute(&mut self, contract_input: &Calldata) -> RunResult {
+        let (action, mut exec_ctx) =
+            parse_calldata::<ChainActionBlob>(contract_input).map_err(|e| e.to_string())?;
+
+        // Not an identity provider
+        if contract_input
+            .identity
+            .0
+            .ends_with(&exec_ctx.contract_name.0)
+        {
+            return Err("Invalid identity provider".to_string());
+        }
+
+        let Some(ref ctx) = contract_input.tx_ctx else {
+            return Err("Missing transaction context".into());
+        };
+
+        // Rollup mode, ensure everything is sent to the same lane ID or we are well past interaction timeout
+        let interaction_timeout = ctx.timestamp.0.saturating_add(60 * 60 * 24 * 1000); // 24 hours
+        if self.lane_id == LaneId::default() || ctx.timestamp.0 > interaction_timeout {
+            self.lane_id = ctx.lane_id.clone();
+        } else if self.lane_id != ctx.lane_id {
+            return Err("Invalid lane ID".into());
+        }
+
+        let events = self
+            .process_chain_action(
+                &contract_input.identity,
+                &action.1,
+                ctx.timestamp.0,
+                Some((&action, &mut exec_ctx)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        self.last_interaction_time = ctx.timestamp.0;
+
+        Ok((borsh::to_vec(&events).unwrap(), exec_ctx, vec![]))
+    }
+
+    fn commit(&self) -> StateCommitment {
+        StateCommitment(borsh::to_vec(self).unwrap())
+    }
+}
+
+impl ChainAction {
+    pub fn table_id(&self) -> TableId {
+        match self {
+            ChainAction::InitMinigame { table_id, .. }
+            | ChainAction::Commit { table_id, .. }
+            | ChainAction::Reveal { table_id, .. }
+            | ChainAction::Done { table_id } => *table_id,
+        }
+    }
+}
+
+/// What players commit to: SHA-256 of the choice (0 to share, 1 to hoard) followed by a secret salt.
+pub fn choice_commitment(choice: Choice, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([choice as u8]);
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+impl Split {
+    /// Choices can be revealed once everyone committed, or the commit window closed.
+    pub fn reveals_open(&self, timestamp: u128) -> bool {
+        timestamp > self.commit_deadline || self.colonists.values().all(|c| c.commitment.is_some())
+    }
+
+    /// Whether the split can be settled: every committed choice is revealed, or it is too late to.
+    pub fn is_over(&self, timestamp: u128) -> bool {
+        timestamp > self.reveal_deadline
+            || (self.reveals_open(timestamp)
+                && self
+                    .colonists
+                    .values()
+                    .all(|c| c.commitment.is_none() || c.choice.is_some()))
+    }
+
+    /// How many colonists revealed each choice, as (sharers, hoarders).
+    pub fn revealed_choices(&self) -> (usize, usize) {
+        let revealed = self.colonists.values().filter_map(|c| c.choice);
+        let hoarders = revealed.clone().filter(|c| *c == Choice::Hoard).count();
+        (revealed.count() - hoarders, hoarders)
+    }
+
+    pub fn pot(&self) -> Result<Amount> {
+        self.colonists
+            .values()
+            .try_fold(0, |pot, c| amount::checked_add(pot, c.ante))
+    }
+
+    /// Every ante goes into the pot, which is worth `RESOURCE_MULTIPLIER` times more when shared.
+    /// Among the colonists who revealed their choice:
+    /// - if everyone shares, the resources are split evenly,
+    /// - if some hoard, the hoarders split the resources and sharers get nothing,
+    /// - if everyone hoards, the resources are lost and antes are returned.
+    /// Colonists who did not reveal forfeit their ante, so hiding a choice is never worth it.
+    pub fn final_results(&self) -> Result<Vec<(Identity, AmountDelta)>> {
+        let resources = amount::checked_mul(self.pot()?, RESOURCE_MULTIPLIER)?;
+        let (sharers, hoarders) = self.revealed_choices();
+        let (sharers, hoarders) = (sharers as Amount, hoarders as Amount);
+
+        let mut results = Vec::new();
+        for (id, colonist) in &self.colonists {
+            let payout = match colonist.choice {
+                None => 0,
+                Some(_) if sharers == 0 => colonist.ante,
+                Some(Choice::Share) if hoarders == 0 => resources / sharers,
+                Some(Choice::Share) => 0,
+                Some(Choice::Hoard) => resources / hoarders,
+            };
+            results.push((id.clone(), amount::delta_between(colonist.ante, payout)?));
+        }
+        Ok(results)
+    }
+}
+
+impl GameState {
+    pub fn new(board_contract: ContractName, backend_identity: Identity) -> Self {
+        Self {
+            splits: BTreeMap::new(),
+            board_contract,
+            backend_identity,
+            last_interaction_time: 0,
+            lane_id: LaneId::default(),
+        }
+    }
+
+    pub fn process_chain_action(
+        &mut self,
+        identity: &Identity,
+        action: &ChainAction,
+        timestamp: u128,
+        ctx: Option<(&ChainActionBlob, &mut ExecutionContext)>,
+    ) -> Result<Vec<ChainEvent>> {
+        let mut events = Vec::new();
+
+        match action {
+            ChainAction::InitMinigame { table_id, players } => {
+                if self.splits.contains_key(table_id) {
+                    return Err(anyhow!("A split is already running at table {}", table_id));
+                }
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // Check our data matches the board contract
+                    minigame_interface::verify_board_start::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        players,
+                        None,
+                    )?;
+                }
+
+                let colonists = players
+                    .iter()
+                    .map(|(id, name, ante, _)| {
+                        (
+                            id.clone(),
+                            Colonist {
+                                id: id.clone(),
+                                name: name.clone(),
+                                ante: *ante,
+                                commitment: None,
+                                choice: None,
+                            },
+                        )
+                    })
+                    .collect();
+                let commit_deadline = timestamp.saturating_add(COMMIT_WINDOW_MS);
+                self.splits.insert(
+                    *table_id,
+                    Split {
+                        colonists,
+                        commit_deadline,
+                        reveal_deadline: commit_deadline.saturating_add(REVEAL_WINDOW_MS),
+                    },
+                );
+
+                events.push(ChainEvent::MinigameInitialized {
+                    table_id: *table_id,
+                    player_count: players.len(),
+                });
+            }
+
+            ChainAction::Commit {
+                table_id,
+                player_id,
+                commitment,
+            } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+                let split = self.get_split_mut(*table_id)?;
+                if split.reveals_open(timestamp) {
+                    return Err(anyhow!("Choices can no longer be committed"));
+                }
+                let Some(colonist) = split.colonists.get_mut(player_id) else {
+                    return Err(anyhow!("Player not found"));
+                };
+                // Players may change their mind until the commit window closes.
+                colonist.commitment = Some(*commitment);
+
+                events.push(ChainEvent::ChoiceCommitted {
+                    table_id: *table_id,
+                    player_id: player_id.clone(),
+                });
+            }
+
+            ChainAction::Reveal {
+                table_id,
+                player_id,
+                choice,
+                salt,
+            } => {
+                if identity != player_id {
+                    return Err(anyhow!("Player ID does not match the action sender"));
+                }
+                let split = self.get_split_mut(*table_id)?;
+                if !split.reveals_open(timestamp) {
+                    return Err(anyhow!("Choices cannot be revealed yet"));
+                }
+                if timestamp > split.reveal_deadline {
+                    return Err(anyhow!("Choices can no longer be revealed"));
+                }
+                let Some(colonist) = split.colonists.get_mut(player_id) else {
+                    return Err(anyhow!("Player not found"));
+                };
+                if colonist.choice.is_some() {
+                    return Err(anyhow!("Choice already revealed"));
+                }
+                if colonist.commitment != Some(choice_commitment(*choice, salt)) {
+                    return Err(anyhow!("Choice does not match the commitment"));
+                }
+                colonist.choice = Some(*choice);
+
+                events.push(ChainEvent::ChoiceRevealed {
+                    table_id: *table_id,
+                    player_id: player_id.clone(),
+                    choice: *choice,
+                });
+            }
+
+            ChainAction::Done { table_id } => {
+                let split = self.get_split_mut(*table_id)?;
+                if !split.is_over(timestamp) {
+                    return Err(anyhow!("Cannot end the split while choices are pending"));
+                }
+                let expected_final_results = split.final_results()?;
+                let pot = split.pot()?;
+                let (sharers, hoarders) = split.revealed_choices();
+
+                if let Some((blob, exec_ctx)) = ctx {
+                    // When ending the minigame, verify that the board game is being updated with the correct data
+                    minigame_interface::verify_board_end::<GameActionBlob>(
+                        exec_ctx,
+                        &self.board_contract,
+                        blob.0,
+                        *table_id,
+                        &expected_final_results,
+                    )?;
+                }
+
+                self.splits.remove(table_id);
+                events.push(ChainEvent::ResourcesSplit {
+                    table_id: *table_id,
+                    sharers,
+                    hoarders,
+                    pot,
+                });
+                events.push(ChainEvent::MinigameEnded {
+                    table_id: *table_id,
+                    final_results: expected_final_results,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn get_split_mut(&mut self, table_id: TableId) -> Result<&mut Split> {
+        self.splits
+            .get_mut(&table_id)
+            .ok_or_else(|| anyhow!("No split running at table {}", table_id))
+    }
+}
@@ -0,0 +1,73 @@
+use core::fmt;
+
+use crate::{ChainEvent, Choice};
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Choice::Share => write!(f, "share"),
+            Choice::Hoard => write!(f, "hoard"),
+        }
+    }
+}
+
+impl fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEvent::MinigameInitialized {
+                table_id,
+                player_count,
+            } => {
+                write!(
+                    f,
+                    "Resource split initialized at table {} with {} players",
+                    table_id, player_count
+                )
+            }
+            ChainEvent::ChoiceCommitted {
+                table_id,
+                player_id,
+            } => {
+                write!(
+                    f,
+                    "Player {} committed a choice at table {}",
+                    player_id, table_id
+                )
+            }
+            ChainEvent::ChoiceRevealed {
+                table_id,
+                player_id,
+                choice,
+            } => {
+                write!(
+                    f,
+                    "Player {} chose to {} at table {}",
+                    player_id, choice, table_id
+                )
+            }
+            ChainEvent::ResourcesSplit {
+                table_id,
+                sharers,
+                hoarders,
+                pot,
+            } => {
+                write!(
+                    f,
+                    "A pot of {} was split between {} sharers and {} hoarders at table {}",
+                    pot, sharers, hoarders, table_id
+                )
+            }
+            ChainEvent::MinigameEnded {
+                table_id,
+                final_results,
+            } => {
+                write!(
+                    f,
+                    "Resource split ended at table {} with {} player results",
+                    table_id,
+                    final_results.len()
+                )
+            }
+        }
+    }
+}
@@ -64,6 +64,10 @@ async fn main() -> Result<()> {
         data_directory: config.data_directory.clone(),
         board_game: ContractName::new(config.contracts.board_game.clone()),
         crash_game: ContractName::new(config.contracts.crash_game.clone()),
+        auction_game: ContractName::new(config.contracts.auction_game.clone()),
+        dice_duel: ContractName::new(config.contracts.dice_duel.clone()),
+        resource_split: ContractName::new(config.contracts.resource_split.clone()),
+        lottery: ContractName::new(config.contracts.lottery.clone()),
     });
 
     tracing::info!("Setting up modules");
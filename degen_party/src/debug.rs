@@ -1,6 +1,6 @@
 #![cfg(feature = "ui")]
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 use std::{cmp::Ordering, collections::HashMap};
 
@@ -129,8 +129,10 @@ impl Module for DebugAnalyzer {
             ))),
         };
         let crash_game_state: ::crash_game::GameState = ::crash_game::GameState::new(
-            self.board_game.clone(),
+            BTreeSet::from([self.board_game.clone()]),
             Identity::new(format!("{}@secp256k1", self.crypto.public_key,)),
+            self.crypto.crash_chain_head(0),
+            crate::CRASH_CHAIN_LENGTH,
         );
         let crash_game_executor = CrashGameExecutor {
             state: crash_game_state,
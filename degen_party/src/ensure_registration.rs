@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use anyhow::Result;
 use client_sdk::rest_client::{NodeApiClient, NodeApiHttpClient};
@@ -27,6 +27,14 @@ pub struct EnsureRegistration {
     board_game: ContractName,
     #[allow(unused)]
     crash_game: ContractName,
+    #[allow(unused)]
+    auction_game: ContractName,
+    #[allow(unused)]
+    dice_duel: ContractName,
+    #[allow(unused)]
+    resource_split: ContractName,
+    #[allow(unused)]
+    lottery: ContractName,
 }
 
 impl Module for EnsureRegistration {
@@ -41,42 +49,81 @@ impl Module for EnsureRegistration {
             hyle_client,
             board_game: ctx.board_game.clone(),
             crash_game: ctx.crash_game.clone(),
+            auction_game: ctx.auction_game.clone(),
+            dice_duel: ctx.dice_duel.clone(),
+            resource_split: ctx.resource_split.clone(),
+            lottery: ctx.lottery.clone(),
         };
 
-        let a = ctx.client.get_contract(ctx.board_game.clone()).await;
-        let b = ctx.client.get_contract(ctx.crash_game.clone()).await;
-
-        if let (Ok(_), Ok(_)) = (a, b) {
-            tracing::info!("Contracts already registered");
-            return Ok(module);
-        }
-
-        module
-            .register_contract(
+        let backend_identity = Identity::new(format!("{}@secp256k1", ctx.crypto.public_key));
+        let contracts = [
+            (
                 ctx.board_game.clone(),
-                board_game::game::GameState::new(Identity::new(format!(
-                    "{}@secp256k1",
-                    ctx.crypto.public_key
-                )))
-                .commit(),
-            )
-            .await?;
-        module
-            .register_contract(
+                board_game::game::GameState::new(backend_identity.clone()).commit(),
+            ),
+            (
                 ctx.crash_game.clone(),
                 crash_game::GameState::new(
+                    BTreeSet::from([ctx.board_game.clone()]),
+                    backend_identity.clone(),
+                    ctx.crypto.crash_chain_head(0),
+                    crate::CRASH_CHAIN_LENGTH,
+                )
+                .commit(),
+            ),
+            (
+                ctx.auction_game.clone(),
+                auction_game::GameState::new(ctx.board_game.clone(), backend_identity.clone())
+                    .commit(),
+            ),
+            (
+                ctx.dice_duel.clone(),
+                dice_duel::GameState::new(ctx.board_game.clone(), backend_identity.clone())
+                    .commit(),
+            ),
+            (
+                ctx.resource_split.clone(),
+                resource_split::GameState::new(ctx.board_game.clone(), backend_identity.clone())
+                    .commit(),
+            ),
+            (
+                ctx.lottery.clone(),
+                lottery::GameState::new(
                     ctx.board_game.clone(),
-                    Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+                    backend_identity.clone(),
+                    ctx.crypto.lottery_chain_head(0),
+                    crate::LOTTERY_CHAIN_LENGTH,
                 )
                 .commit(),
-            )
-            .await?;
+            ),
+        ];
+
+        // Only the missing contracts are registered, the live ones keep their state.
+        let mut registered_any = false;
+        for (contract_name, state_commitment) in contracts {
+            if ctx.client.get_contract(contract_name.clone()).await.is_ok() {
+                continue;
+            }
+            tracing::info!("Registering contract {}", contract_name);
+            module
+                .register_contract(contract_name, state_commitment)
+                .await?;
+            registered_any = true;
+        }
+        if !registered_any {
+            tracing::info!("Contracts already registered");
+            return Ok(module);
+        }
 
         tokio::time::timeout(std::time::Duration::from_secs(60), async {
             loop {
                 let a = ctx.client.get_contract(ctx.board_game.clone()).await;
                 let b = ctx.client.get_contract(ctx.crash_game.clone()).await;
-                if let (Ok(_), Ok(_)) = (a, b) {
+                let c = ctx.client.get_contract(ctx.auction_game.clone()).await;
+                let d = ctx.client.get_contract(ctx.dice_duel.clone()).await;
+                let e = ctx.client.get_contract(ctx.resource_split.clone()).await;
+                let f = ctx.client.get_contract(ctx.lottery.clone()).await;
+                if let (Ok(_), Ok(_), Ok(_), Ok(_), Ok(_), Ok(_)) = (a, b, c, d, e, f) {
                     break;
                 }
                 tokio::time::sleep(std::time::Duration::from_millis(250)).await;
@@ -118,6 +165,14 @@ impl EnsureRegistration {
                         contracts::BOARD_GAME_ELF
                     } else if contract_name == self.crash_game {
                         contracts::CRASH_GAME_ELF
+                    } else if contract_name == self.auction_game {
+                        contracts::AUCTION_GAME_ELF
+                    } else if contract_name == self.dice_duel {
+                        contracts::DICE_DUEL_ELF
+                    } else if contract_name == self.resource_split {
+                        contracts::RESOURCE_SPLIT_ELF
+                    } else if contract_name == self.lottery {
+                        contracts::LOTTERY_ELF
                     } else {
                         bail!("Unknown contract name: {}", contract_name)
                     }
@@ -142,6 +197,14 @@ impl EnsureRegistration {
                             contracts::BOARD_GAME_ELF
                         } else if contract_name == self.crash_game {
                             contracts::CRASH_GAME_ELF
+                        } else if contract_name == self.auction_game {
+                            contracts::AUCTION_GAME_ELF
+                        } else if contract_name == self.dice_duel {
+                            contracts::DICE_DUEL_ELF
+                        } else if contract_name == self.resource_split {
+                            contracts::RESOURCE_SPLIT_ELF
+                        } else if contract_name == self.lottery {
+                            contracts::LOTTERY_ELF
                         } else {
                             bail!("Unknown contract name: {}", contract_name)
                         }
@@ -3,10 +3,15 @@ use std::{path::PathBuf, sync::Arc};
 use client_sdk::rest_client::NodeApiHttpClient;
 use config::{Config, Environment};
 use hyle_modules::modules::websocket::WebSocketConfig;
+use rollup_execution::auction_game::{AuctionGameCommand, AuctionGameEvent};
 use rollup_execution::crash_game::{CrashGameCommand, CrashGameEvent};
+use rollup_execution::dice_duel::{DiceDuelCommand, DiceDuelEvent};
 use rollup_execution::game_state::{GameStateCommand, GameStateEvent};
+use rollup_execution::lottery::LotteryEvent;
+use rollup_execution::resource_split::{ResourceSplitCommand, ResourceSplitEvent};
 use sdk::{Blob, ContractName, Identity};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 pub mod debug;
 pub mod ensure_registration;
@@ -20,6 +25,46 @@ pub struct CryptoContext {
     pub public_key: secp256k1::PublicKey,
 }
 
+/// How many crash rounds each committed hash chain covers.
+pub const CRASH_CHAIN_LENGTH: u32 = 100_000;
+/// A new crash hash chain is committed to once fewer elements than this are left.
+pub const CRASH_CHAIN_ROTATION_MARGIN: u64 = 1_000;
+/// How many lottery draws each committed hash chain covers.
+pub const LOTTERY_CHAIN_LENGTH: u32 = 100_000;
+/// A new lottery hash chain is committed to once fewer elements than this are left.
+pub const LOTTERY_CHAIN_ROTATION_MARGIN: u64 = 1_000;
+
+impl CryptoContext {
+    /// First element of the crash hash chain of `generation`, derived from the backend key
+    /// so it never needs storing.
+    pub fn crash_chain_seed(&self, generation: u32) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.secret_key.secret_bytes());
+        hasher.update(b"crash");
+        hasher.update(generation.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    /// The chain head the crash game commits to, at registration for generation 0.
+    pub fn crash_chain_head(&self, generation: u32) -> [u8; 32] {
+        crash_game::fairness::chain_end(self.crash_chain_seed(generation), CRASH_CHAIN_LENGTH)
+    }
+
+    /// First element of the lottery hash chain of `generation`, independent from the crash ones.
+    pub fn lottery_chain_seed(&self, generation: u32) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.secret_key.secret_bytes());
+        hasher.update(b"lottery");
+        hasher.update(generation.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    /// The chain head the lottery commits to, at registration for generation 0.
+    pub fn lottery_chain_head(&self, generation: u32) -> [u8; 32] {
+        lottery::fairness::chain_end(self.lottery_chain_seed(generation), LOTTERY_CHAIN_LENGTH)
+    }
+}
+
 pub struct Context {
     pub config: Arc<Conf>,
     pub client: Arc<NodeApiHttpClient>,
@@ -27,6 +72,10 @@ pub struct Context {
     pub data_directory: PathBuf,
     pub board_game: ContractName,
     pub crash_game: ContractName,
+    pub auction_game: ContractName,
+    pub dice_duel: ContractName,
+    pub resource_split: ContractName,
+    pub lottery: ContractName,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +92,9 @@ pub struct AuthenticatedMessage<T> {
 pub enum InboundWebsocketMessage {
     GameState(GameStateCommand),
     CrashGame(CrashGameCommand),
+    AuctionGame(AuctionGameCommand),
+    DiceDuel(DiceDuelCommand),
+    ResourceSplit(ResourceSplitCommand),
 }
 
 /// Messages sent to WebSocket clients from the system
@@ -51,12 +103,20 @@ pub enum InboundWebsocketMessage {
 pub enum OutboundWebsocketMessage {
     GameStateEvent(GameStateEvent),
     CrashGame(CrashGameEvent),
+    AuctionGame(AuctionGameEvent),
+    DiceDuel(DiceDuelEvent),
+    ResourceSplit(ResourceSplitEvent),
+    Lottery(LotteryEvent),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ContractsConf {
     pub board_game: String,
     pub crash_game: String,
+    pub auction_game: String,
+    pub dice_duel: String,
+    pub resource_split: String,
+    pub lottery: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -70,6 +130,8 @@ pub struct Conf {
     pub buffer_blocks: u32,
     pub max_txs_per_proof: usize,
     pub tx_working_window_size: usize,
+    /// Minimum delay between two crash multiplier ticks sent to clients, in milliseconds.
+    pub crash_tick_interval_ms: u64,
 
     pub start_block: u64,
 
@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::BTreeSet, path::Path, sync::Arc};
 
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -71,17 +71,19 @@ impl TxExecutorHandler for CrashGameExecutor {
             )
         {
             tracing::warn!("Received ChainActionBlob: {:?}", parameters);
-            if let ChainAction::InitMinigame { time, .. } = parameters.1 {
-                /*let mut state = GameState::new(
-                    self.board_game.clone(),
-                    Identity::new(format!("{}@secp256k1", self.crypto.public_key)),
-                );
-                */
-                self.state.minigame_backend.game_setup_time = Some(time as u128);
-                self.state.minigame_backend.current_time = Some(time as u128);
-            } else if let ChainAction::Start { time } = parameters.1 {
-                self.state.minigame_backend.game_start_time = Some(time as u128);
-                self.state.minigame_backend.current_time = Some(time as u128);
+            let ChainActionBlob(_, key, action) = parameters;
+            if let Some(round) = self.state.rounds.get_mut(&key) {
+                if let ChainAction::InitMinigame { time, .. } | ChainAction::StartRound { time } =
+                    action
+                {
+                    round.minigame_backend.game_setup_time = Some(time as u128);
+                    round.minigame_backend.current_time = Some(time as u128);
+                } else if let ChainAction::Start { time } = action {
+                    // Follow the on-chain curve, so that cash-outs never claim more than it allows.
+                    round.minigame_backend.game_start_time =
+                        Some(round.minigame_verifiable.started_at);
+                    round.minigame_backend.current_time = Some(time as u128);
+                }
             }
         }
 
@@ -101,6 +103,131 @@ impl TxExecutorHandler for CrashGameExecutor {
         self.state.commit()
     }
 
+    fn construct_state(
+        _register_blob: &RegisterContractEffect,
+        metadata: &Option<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        if let Some(metadata) = metadata {
+            let (boards, backend_identity, chain_head, chain_length) = borsh::from_slice(metadata)?;
+            Ok(Self {
+                state: crash_game::GameState::new(
+                    boards,
+                    backend_identity,
+                    chain_head,
+                    chain_length,
+                ),
+            })
+        } else {
+            anyhow::bail!("No metadata provided");
+        }
+    }
+}
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct AuctionGameExecutor {
+    pub state: auction_game::GameState,
+}
+
+impl TxExecutorHandler for AuctionGameExecutor {
+    fn handle(&mut self, calldata: &sdk::Calldata) -> Result<sdk::HyleOutput> {
+        let initial_state_commitment = self.state.commit();
+        let mut res = self.state.execute(calldata);
+        Ok(as_hyle_output(
+            initial_state_commitment,
+            self.state.commit(),
+            calldata,
+            &mut res,
+        ))
+    }
+
+    fn build_commitment_metadata(&self, _blob: &sdk::Blob) -> Result<Vec<u8>> {
+        Ok(self.state.commit().0)
+    }
+
+    fn get_state_commitment(&self) -> StateCommitment {
+        self.state.commit()
+    }
+
+    fn construct_state(
+        _register_blob: &RegisterContractEffect,
+        metadata: &Option<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        if let Some(metadata) = metadata {
+            let (board_contract, backend_identity) = borsh::from_slice(metadata)?;
+            Ok(Self {
+                state: auction_game::GameState::new(board_contract, backend_identity),
+            })
+        } else {
+            anyhow::bail!("No metadata provided");
+        }
+    }
+}
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct DiceDuelExecutor {
+    pub state: dice_duel::GameState,
+}
+
+impl TxExecutorHandler for DiceDuelExecutor {
+    fn handle(&mut self, calldata: &sdk::Calldata) -> Result<sdk::HyleOutput> {
+        let initial_state_commitment = self.state.commit();
+        let mut res = self.state.execute(calldata);
+        Ok(as_hyle_output(
+            initial_state_commitment,
+            self.state.commit(),
+            calldata,
+            &mut res,
+        ))
+    }
+
+    fn build_commitment_metadata(&self, _blob: &sdk::Blob) -> Result<Vec<u8>> {
+        Ok(self.state.commit().0)
+    }
+
+    fn get_state_commitment(&self) -> StateCommitment {
+        self.state.commit()
+    }
+
+    fn construct_state(
+        _register_blob: &RegisterContractEffect,
+        metadata: &Option<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        if let Some(metadata) = metadata {
+            let (board_contract, backend_identity) = borsh::from_slice(metadata)?;
+            Ok(Self {
+                state: dice_duel::GameState::new(board_contract, backend_identity),
+            })
+        } else {
+            anyhow::bail!("No metadata provided");
+        }
+    }
+}
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct ResourceSplitExecutor {
+    pub state: resource_split::GameState,
+}
+
+impl TxExecutorHandler for ResourceSplitExecutor {
+    fn handle(&mut self, calldata: &sdk::Calldata) -> Result<sdk::HyleOutput> {
+        let initial_state_commitment = self.state.commit();
+        let mut res = self.state.execute(calldata);
+        Ok(as_hyle_output(
+            initial_state_commitment,
+            self.state.commit(),
+            calldata,
+            &mut res,
+        ))
+    }
+
+    fn build_commitment_metadata(&self, _blob: &sdk::Blob) -> Result<Vec<u8>> {
+        Ok(self.state.commit().0)
+    }
+
+    fn get_state_commitment(&self) -> StateCommitment {
+        self.state.commit()
+    }
+
     fn construct_state(
         _register_blob: &RegisterContractEffect,
         metadata: &Option<Vec<u8>>,
@@ -108,7 +235,53 @@ impl TxExecutorHandler for CrashGameExecutor {
         if let Some(metadata) = metadata {
             let (board_contract, backend_identity) = borsh::from_slice(metadata)?;
             Ok(Self {
-                state: crash_game::GameState::new(board_contract, backend_identity),
+                state: resource_split::GameState::new(board_contract, backend_identity),
+            })
+        } else {
+            anyhow::bail!("No metadata provided");
+        }
+    }
+}
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct LotteryExecutor {
+    pub state: lottery::GameState,
+}
+
+impl TxExecutorHandler for LotteryExecutor {
+    fn handle(&mut self, calldata: &sdk::Calldata) -> Result<sdk::HyleOutput> {
+        let initial_state_commitment = self.state.commit();
+        let mut res = self.state.execute(calldata);
+        Ok(as_hyle_output(
+            initial_state_commitment,
+            self.state.commit(),
+            calldata,
+            &mut res,
+        ))
+    }
+
+    fn build_commitment_metadata(&self, _blob: &sdk::Blob) -> Result<Vec<u8>> {
+        Ok(self.state.commit().0)
+    }
+
+    fn get_state_commitment(&self) -> StateCommitment {
+        self.state.commit()
+    }
+
+    fn construct_state(
+        _register_blob: &RegisterContractEffect,
+        metadata: &Option<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        if let Some(metadata) = metadata {
+            let (board_contract, backend_identity, chain_head, chain_length) =
+                borsh::from_slice(metadata)?;
+            Ok(Self {
+                state: lottery::GameState::new(
+                    board_contract,
+                    backend_identity,
+                    chain_head,
+                    chain_length,
+                ),
             })
         } else {
             anyhow::bail!("No metadata provided");
@@ -127,12 +300,40 @@ pub async fn setup_auto_provers(
         ))),
     };
     let crash_game_state: crash_game::GameState = crash_game::GameState::new(
-        ctx.board_game.clone(),
+        BTreeSet::from([ctx.board_game.clone()]),
         Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ctx.crypto.crash_chain_head(0),
+        crate::CRASH_CHAIN_LENGTH,
     );
     let crash_game_executor = CrashGameExecutor {
         state: crash_game_state,
     };
+    let auction_game_executor = AuctionGameExecutor {
+        state: auction_game::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let dice_duel_executor = DiceDuelExecutor {
+        state: dice_duel::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let resource_split_executor = ResourceSplitExecutor {
+        state: resource_split::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let lottery_executor = LotteryExecutor {
+        state: lottery::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+            ctx.crypto.lottery_chain_head(0),
+            crate::LOTTERY_CHAIN_LENGTH,
+        ),
+    };
     #[cfg(not(feature = "fake_proofs"))]
     let board_game_prover = {
         let pk = load_pk(
@@ -188,6 +389,110 @@ pub async fn setup_auto_provers(
         }))
         .await?;
 
+    #[cfg(not(feature = "fake_proofs"))]
+    let auction_game_prover = {
+        let pk = load_pk(
+            contracts::AUCTION_GAME_ELF,
+            &ctx.data_directory.join("auction_game_pk.json"),
+        );
+        Arc::new(client_sdk::helpers::sp1::SP1Prover::new(pk).await)
+    };
+    #[cfg(feature = "fake_proofs")]
+    let auction_game_prover = Arc::new(client_sdk::helpers::test::TxExecutorTestProver::<
+        auction_game::GameState,
+    >::new());
+
+    handler
+        .build_module::<AutoProver<AuctionGameExecutor>>(Arc::new(AutoProverCtx {
+            data_directory: ctx.data_directory.clone(),
+            prover: auction_game_prover,
+            contract_name: ctx.auction_game.clone(),
+            node: ctx.client.clone(),
+            default_state: auction_game_executor,
+            buffer_blocks: ctx.config.buffer_blocks,
+            max_txs_per_proof: ctx.config.max_txs_per_proof,
+            tx_working_window_size: ctx.config.tx_working_window_size,
+        }))
+        .await?;
+
+    #[cfg(not(feature = "fake_proofs"))]
+    let dice_duel_prover = {
+        let pk = load_pk(
+            contracts::DICE_DUEL_ELF,
+            &ctx.data_directory.join("dice_duel_pk.json"),
+        );
+        Arc::new(client_sdk::helpers::sp1::SP1Prover::new(pk).await)
+    };
+    #[cfg(feature = "fake_proofs")]
+    let dice_duel_prover = Arc::new(client_sdk::helpers::test::TxExecutorTestProver::<
+        dice_duel::GameState,
+    >::new());
+
+    handler
+        .build_module::<AutoProver<DiceDuelExecutor>>(Arc::new(AutoProverCtx {
+            data_directory: ctx.data_directory.clone(),
+            prover: dice_duel_prover,
+            contract_name: ctx.dice_duel.clone(),
+            node: ctx.client.clone(),
+            default_state: dice_duel_executor,
+            buffer_blocks: ctx.config.buffer_blocks,
+            max_txs_per_proof: ctx.config.max_txs_per_proof,
+            tx_working_window_size: ctx.config.tx_working_window_size,
+        }))
+        .await?;
+
+    #[cfg(not(feature = "fake_proofs"))]
+    let resource_split_prover = {
+        let pk = load_pk(
+            contracts::RESOURCE_SPLIT_ELF,
+            &ctx.data_directory.join("resource_split_pk.json"),
+        );
+        Arc::new(client_sdk::helpers::sp1::SP1Prover::new(pk).await)
+    };
+    #[cfg(feature = "fake_proofs")]
+    let resource_split_prover = Arc::new(client_sdk::helpers::test::TxExecutorTestProver::<
+        resource_split::GameState,
+    >::new());
+
+    handler
+        .build_module::<AutoProver<ResourceSplitExecutor>>(Arc::new(AutoProverCtx {
+            data_directory: ctx.data_directory.clone(),
+            prover: resource_split_prover,
+            contract_name: ctx.resource_split.clone(),
+            node: ctx.client.clone(),
+            default_state: resource_split_executor,
+            buffer_blocks: ctx.config.buffer_blocks,
+            max_txs_per_proof: ctx.config.max_txs_per_proof,
+            tx_working_window_size: ctx.config.tx_working_window_size,
+        }))
+        .await?;
+
+    #[cfg(not(feature = "fake_proofs"))]
+    let lottery_prover = {
+        let pk = load_pk(
+            contracts::LOTTERY_ELF,
+            &ctx.data_directory.join("lottery_pk.json"),
+        );
+        Arc::new(client_sdk::helpers::sp1::SP1Prover::new(pk).await)
+    };
+    #[cfg(feature = "fake_proofs")]
+    let lottery_prover = Arc::new(client_sdk::helpers::test::TxExecutorTestProver::<
+        lottery::GameState,
+    >::new());
+
+    handler
+        .build_module::<AutoProver<LotteryExecutor>>(Arc::new(AutoProverCtx {
+            data_directory: ctx.data_directory.clone(),
+            prover: lottery_prover,
+            contract_name: ctx.lottery.clone(),
+            node: ctx.client.clone(),
+            default_state: lottery_executor,
+            buffer_blocks: ctx.config.buffer_blocks,
+            max_txs_per_proof: ctx.config.max_txs_per_proof,
+            tx_working_window_size: ctx.config.tx_working_window_size,
+        }))
+        .await?;
+
     Ok(())
 }
 
@@ -1,12 +1,11 @@
-use ::crash_game::ChainEvent;
+use ::crash_game::{ChainEvent, RoundKey};
 use anyhow::Context as _;
 use anyhow::Result;
-use board_game::game::GameEvent;
+use board_game::game::{GameEvent, TableId};
+use board_game::GameActionBlob;
 use borsh::{BorshDeserialize, BorshSerialize};
 use client_sdk::rest_client::NodeApiClient;
 use client_sdk::transaction_builder::TxExecutorHandler;
-use crash_game::CrashGameEvent;
-use game_state::GameStateEvent;
 use hyle_modules::{
     bus::{BusClientReceiver, BusClientSender, SharedMessageBus},
     log_error, module_bus_client, module_handle_messages,
@@ -17,8 +16,8 @@ use hyle_modules::{
 };
 use sdk::{
     hyle_model_utils::TimestampMs, BlobTransaction, BlockHeight, Calldata, ContractName, Hashed,
-    HyleOutput, Identity, LaneId, MempoolStatusEvent, NodeStateEvent, TransactionData, TxContext,
-    TxHash, TxId,
+    HyleOutput, Identity, LaneId, MempoolStatusEvent, NodeStateEvent, StructuredBlobData,
+    TransactionData, TxContext, TxHash, TxId,
 };
 use smt_token::client::tx_executor_handler::SmtTokenProvableState;
 use std::fmt;
@@ -27,7 +26,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 use std::{
@@ -41,13 +40,23 @@ use wallet::client::tx_executor_handler::Wallet;
 
 use crate::{
     fake_lane_manager::ConfirmedBlobTransaction,
-    proving::{BoardGameExecutor, CrashGameExecutor},
+    proving::{
+        AuctionGameExecutor, BoardGameExecutor, CrashGameExecutor, DiceDuelExecutor,
+        LotteryExecutor, ResourceSplitExecutor,
+    },
     AuthenticatedMessage, Context, CryptoContext, InboundWebsocketMessage,
     OutboundWebsocketMessage,
 };
 
+pub mod auction_game;
 pub mod crash_game;
+pub mod dice_duel;
 pub mod game_state;
+pub mod lottery;
+pub mod resource_split;
+
+/// How many websocket message UUIDs are remembered to refuse replays.
+const MAX_RELAYED_UUIDS: usize = 10_000;
 
 pub struct RollupExecutor {
     bus: RollupExecutorBusClient,
@@ -55,7 +64,29 @@ pub struct RollupExecutor {
     crypto: Arc<CryptoContext>,
     store: RollupExecutorStore,
     // Convenience, TODO refactor this ?
-    last_claim_reward: Instant,
+    last_claim_reward: HashMap<TableId, Instant>,
+    last_seed_reveal: HashMap<TableId, Instant>,
+    last_minigame_end: HashMap<(ContractName, TableId), Instant>,
+    last_duel_round: HashMap<TableId, (u32, Instant)>,
+    last_crash_start: Option<Instant>,
+    last_crash_rotation: Option<Instant>,
+    last_lottery_rotation: Option<Instant>,
+    last_crash_end: HashMap<RoundKey, Instant>,
+    last_crash_tick: HashMap<RoundKey, Instant>,
+    // Minigames keep their state per table, unlike the crash game.
+    auction_game: ContractName,
+    dice_duel: ContractName,
+    resource_split: ContractName,
+    lottery: ContractName,
+    // UUIDs of the websocket messages already relayed, oldest first.
+    relayed_uuids: VecDeque<u128>,
+    relayed_uuid_set: HashSet<u128>,
+    // Crash chain elements by chain generation and index, computed once per round and
+    // dropped once revealed.
+    crash_seeds: HashMap<(u32, u64), [u8; 32]>,
+    // Same, for the lottery chains.
+    lottery_seeds: HashMap<(u32, u64), [u8; 32]>,
+    crash_tick_interval: std::time::Duration,
 }
 
 impl Deref for RollupExecutor {
@@ -247,7 +278,26 @@ impl Module for RollupExecutor {
             store,
             data_directory,
             crypto: ctx.common.crypto.clone(),
-            last_claim_reward: Instant::now(),
+            last_claim_reward: HashMap::new(),
+            last_seed_reveal: HashMap::new(),
+            last_minigame_end: HashMap::new(),
+            last_duel_round: HashMap::new(),
+            last_crash_start: None,
+            last_crash_rotation: None,
+            last_lottery_rotation: None,
+            last_crash_end: HashMap::new(),
+            last_crash_tick: HashMap::new(),
+            auction_game: ctx.common.auction_game.clone(),
+            dice_duel: ctx.common.dice_duel.clone(),
+            resource_split: ctx.common.resource_split.clone(),
+            lottery: ctx.common.lottery.clone(),
+            relayed_uuids: VecDeque::new(),
+            relayed_uuid_set: HashSet::new(),
+            crash_seeds: HashMap::new(),
+            lottery_seeds: HashMap::new(),
+            crash_tick_interval: std::time::Duration::from_millis(
+                ctx.common.config.crash_tick_interval_ms,
+            ),
         })
     }
 
@@ -280,7 +330,9 @@ impl Module for RollupExecutor {
                     uuid,
                     identity_blobs
                 } = msg.message;
-                if let InboundWebsocketMessage::GameState(event) = message {
+                if let Err(e) = self.check_fresh_uuid(&uuid) {
+                    tracing::warn!("Refusing to relay message: {:?}", e);
+                } else if let InboundWebsocketMessage::GameState(event) = message {
                     if let Err(e) = self.handle_user_message(event, identity, &uuid, identity_blobs).await {
                         tracing::warn!("Error handling event: {:?}", e);
                     }
@@ -288,6 +340,18 @@ impl Module for RollupExecutor {
                     if let Err(e) = self.handle_player_message(event, identity, &uuid, identity_blobs).await {
                         tracing::warn!("Error handling player message: {:?}", e);
                     }
+                } else if let InboundWebsocketMessage::AuctionGame(event) = message {
+                    if let Err(e) = self.handle_auction_message(event, identity, &uuid, identity_blobs).await {
+                        tracing::warn!("Error handling auction message: {:?}", e);
+                    }
+                } else if let InboundWebsocketMessage::DiceDuel(event) = message {
+                    if let Err(e) = self.handle_dice_duel_message(event, identity, &uuid, identity_blobs).await {
+                        tracing::warn!("Error handling dice duel message: {:?}", e);
+                    }
+                } else if let InboundWebsocketMessage::ResourceSplit(event) = message {
+                    if let Err(e) = self.handle_resource_split_message(event, identity, &uuid, identity_blobs).await {
+                        tracing::warn!("Error handling resource split message: {:?}", e);
+                    }
                 }
             }
             listen<NodeStateEvent> event => {
@@ -303,6 +367,10 @@ impl Module for RollupExecutor {
             _ = update_interval.tick() => {
                 _ = log_error!(self.board_game_on_tick().await, "board game on tick");
                 _ = log_error!(self.crash_game_on_tick().await, "crash game on tick");
+                _ = log_error!(self.auction_game_on_tick().await, "auction game on tick");
+                _ = log_error!(self.dice_duel_on_tick().await, "dice duel on tick");
+                _ = log_error!(self.resource_split_on_tick().await, "resource split on tick");
+                _ = log_error!(self.lottery_on_tick().await, "lottery on tick");
             }
         };
 
@@ -322,6 +390,21 @@ impl Module for RollupExecutor {
 }
 
 impl RollupExecutor {
+    /// Each websocket message carries a fresh UUID, a repeated one is a replay.
+    fn check_fresh_uuid(&mut self, uuid: &str) -> Result<()> {
+        let uuid_128 = uuid::Uuid::parse_str(uuid)?.as_u128();
+        if !self.relayed_uuid_set.insert(uuid_128) {
+            anyhow::bail!("Message {} was already relayed", uuid);
+        }
+        self.relayed_uuids.push_back(uuid_128);
+        if self.relayed_uuids.len() > MAX_RELAYED_UUIDS {
+            if let Some(oldest) = self.relayed_uuids.pop_front() {
+                self.relayed_uuid_set.remove(&oldest);
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_node_state_event(&mut self, event: NodeStateEvent) -> Result<()> {
         match event {
             NodeStateEvent::NewBlock(block) => {
@@ -477,26 +560,46 @@ impl RollupExecutor {
             if contract_name == &self.board_game {
                 let events: Vec<GameEvent> =
                     borsh::from_slice(&hyle_output.program_outputs).unwrap();
-                self.bus.send(WsBroadcastMessage {
-                    message: OutboundWebsocketMessage::GameStateEvent(
-                        GameStateEvent::StateUpdated {
-                            state: Some(self.get_board_game().clone()),
-                            events,
-                            board_game: self.board_game.clone(),
-                            crash_game: self.crash_game.clone(),
-                        },
-                    ),
-                })?;
+                let Some(table_id) = Self::board_table_id(&blob_tx, &self.board_game) else {
+                    continue;
+                };
+                let state = self.get_table(table_id).ok().cloned();
+                self.broadcast_table_state(table_id, state, events)?;
             } else if contract_name == &self.crash_game {
                 let events: Vec<ChainEvent> =
                     borsh::from_slice(&hyle_output.program_outputs).unwrap();
-                let state = Some(self.get_crash_game().clone());
-                self.bus.send(WsBroadcastMessage {
-                    message: OutboundWebsocketMessage::CrashGame(CrashGameEvent::StateUpdated {
-                        state,
-                        events,
-                    }),
-                })?;
+                let Some(key) = self.crash_round_key(&blob_tx) else {
+                    continue;
+                };
+                self.broadcast_crash_state(key, events)?;
+            } else if contract_name == &self.auction_game {
+                let events: Vec<::auction_game::ChainEvent> =
+                    borsh::from_slice(&hyle_output.program_outputs).unwrap();
+                let Some(table_id) = self.auction_table_id(&blob_tx) else {
+                    continue;
+                };
+                self.broadcast_auction_state(table_id, events)?;
+            } else if contract_name == &self.dice_duel {
+                let events: Vec<::dice_duel::ChainEvent> =
+                    borsh::from_slice(&hyle_output.program_outputs).unwrap();
+                let Some(table_id) = self.dice_duel_table_id(&blob_tx) else {
+                    continue;
+                };
+                self.broadcast_dice_duel_state(table_id, events)?;
+            } else if contract_name == &self.resource_split {
+                let events: Vec<::resource_split::ChainEvent> =
+                    borsh::from_slice(&hyle_output.program_outputs).unwrap();
+                let Some(table_id) = self.resource_split_table_id(&blob_tx) else {
+                    continue;
+                };
+                self.broadcast_resource_split_state(table_id, events)?;
+            } else if contract_name == &self.lottery {
+                let events: Vec<::lottery::ChainEvent> =
+                    borsh::from_slice(&hyle_output.program_outputs).unwrap();
+                let Some(table_id) = self.lottery_table_id(&blob_tx) else {
+                    continue;
+                };
+                self.broadcast_lottery_state(table_id, events)?;
             }
         }
 
@@ -504,6 +607,30 @@ impl RollupExecutor {
 
         Ok(())
     }
+
+    /// Whether the backend sent an EndMinigame for this table in the last few seconds.
+    /// Records the attempt otherwise, so that ending transactions are only retried every so often.
+    fn end_recently_sent(&mut self, minigame: ContractName, table_id: TableId) -> bool {
+        let key = (minigame, table_id);
+        let recently_sent = self
+            .last_minigame_end
+            .get(&key)
+            .is_some_and(|last| last.elapsed().as_secs() <= 10);
+        if !recently_sent {
+            self.last_minigame_end.insert(key, Instant::now());
+        }
+        recently_sent
+    }
+
+    /// Finds which board game table a transaction applies to.
+    fn board_table_id(blob_tx: &BlobTransaction, board_game: &ContractName) -> Option<TableId> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| &blob.contract_name == board_game)
+            .find_map(|blob| StructuredBlobData::<GameActionBlob>::try_from(blob.data.clone()).ok())
+            .map(|data| data.parameters.1)
+    }
 }
 
 impl RollupExecutorStore {
@@ -737,14 +864,46 @@ pub async fn setup_rollup_execution(
         ))),
     };
     let crash_game_state: ::crash_game::GameState = ::crash_game::GameState::new(
-        ctx.board_game.clone(),
+        BTreeSet::from([ctx.board_game.clone()]),
         Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ctx.crypto.crash_chain_head(0),
+        crate::CRASH_CHAIN_LENGTH,
     );
     let crash_game_executor = CrashGameExecutor {
         state: crash_game_state,
     };
+    let auction_game_executor = AuctionGameExecutor {
+        state: ::auction_game::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let dice_duel_executor = DiceDuelExecutor {
+        state: ::dice_duel::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let resource_split_executor = ResourceSplitExecutor {
+        state: ::resource_split::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+        ),
+    };
+    let lottery_executor = LotteryExecutor {
+        state: ::lottery::GameState::new(
+            ctx.board_game.clone(),
+            Identity::new(format!("{}@secp256k1", ctx.crypto.public_key,)),
+            ctx.crypto.lottery_chain_head(0),
+            crate::LOTTERY_CHAIN_LENGTH,
+        ),
+    };
     let board_game = ctx.board_game.clone();
     let crash_game = ctx.crash_game.clone();
+    let auction_game = ctx.auction_game.clone();
+    let dice_duel = ctx.dice_duel.clone();
+    let resource_split = ctx.resource_split.clone();
+    let lottery = ctx.lottery.clone();
     handler
         .build_module::<RollupExecutor>(RollupExecutorCtx {
             common: ctx.clone(),
@@ -757,6 +916,22 @@ pub async fn setup_rollup_execution(
                     ctx.crash_game.clone(),
                     ContractBox::new(crash_game_executor.clone()),
                 ),
+                (
+                    ctx.auction_game.clone(),
+                    ContractBox::new(auction_game_executor.clone()),
+                ),
+                (
+                    ctx.dice_duel.clone(),
+                    ContractBox::new(dice_duel_executor.clone()),
+                ),
+                (
+                    ctx.resource_split.clone(),
+                    ContractBox::new(resource_split_executor.clone()),
+                ),
+                (
+                    ctx.lottery.clone(),
+                    ContractBox::new(lottery_executor.clone()),
+                ),
                 (
                     ContractName::new("oxygen"),
                     ContractBox::new(SmtTokenProvableState::default()),
@@ -787,6 +962,24 @@ pub async fn setup_rollup_execution(
                     ContractBox::new(
                         borsh::from_slice::<CrashGameExecutor>(&data).expect("Bad serialized data"),
                     )
+                } else if contract_name == &auction_game {
+                    ContractBox::new(
+                        borsh::from_slice::<AuctionGameExecutor>(&data)
+                            .expect("Bad serialized data"),
+                    )
+                } else if contract_name == &dice_duel {
+                    ContractBox::new(
+                        borsh::from_slice::<DiceDuelExecutor>(&data).expect("Bad serialized data"),
+                    )
+                } else if contract_name == &resource_split {
+                    ContractBox::new(
+                        borsh::from_slice::<ResourceSplitExecutor>(&data)
+                            .expect("Bad serialized data"),
+                    )
+                } else if contract_name == &lottery {
+                    ContractBox::new(
+                        borsh::from_slice::<LotteryExecutor>(&data).expect("Bad serialized data"),
+                    )
                 } else if contract_name == &ContractName::new("oranj")
                     || contract_name == &ContractName::new("oxygen")
                 {
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use auction_game::{Auction, ChainAction, ChainActionBlob, ChainEvent, GameState};
+use board_game::{game::TableId, GameActionBlob};
+use hyle_modules::bus::BusClientSender;
+use hyle_modules::modules::websocket::WsBroadcastMessage;
+use minigame_interface::{Amount, BoardProtocol, MinigameResult};
+use sdk::{Blob, BlobIndex, BlobTransaction, ContractAction, Identity, StructuredBlobData};
+use serde::{Deserialize, Serialize};
+use std::ops::DerefMut;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{proving::AuctionGameExecutor, OutboundWebsocketMessage};
+
+// Message types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum AuctionGameCommand {
+    Commit {
+        table_id: TableId,
+        commitment: [u8; 32],
+    },
+    Reveal {
+        table_id: TableId,
+        bid: Amount,
+        salt: [u8; 32],
+    },
+    End {
+        table_id: TableId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum AuctionGameEvent {
+    StateUpdated {
+        table_id: TableId,
+        state: Option<Auction>,
+        events: Vec<ChainEvent>,
+    },
+}
+
+impl super::RollupExecutor {
+    pub(super) fn get_auction_game(&mut self) -> &mut GameState {
+        let ag = self.auction_game.clone();
+        &mut self
+            .contracts
+            .get_mut(&ag)
+            .expect("Auction game not initialized")
+            .deref_mut()
+            .as_any_mut()
+            .downcast_mut::<AuctionGameExecutor>()
+            .expect("Auction game state is not of the expected type")
+            .state
+    }
+
+    pub(super) async fn handle_auction_message(
+        &mut self,
+        event: AuctionGameCommand,
+        identity: Identity,
+        uuid: &str,
+        identity_blobs: Vec<Blob>,
+    ) -> Result<()> {
+        let uuid_128: u128 = uuid::Uuid::parse_str(uuid)?.as_u128();
+        let mut blobs = match event {
+            AuctionGameCommand::Commit {
+                table_id,
+                commitment,
+            } => vec![ChainActionBlob(
+                uuid_128,
+                ChainAction::Commit {
+                    table_id,
+                    player_id: identity.clone(),
+                    commitment,
+                },
+            )
+            .as_blob(self.auction_game.clone(), None, None)],
+            AuctionGameCommand::Reveal {
+                table_id,
+                bid,
+                salt,
+            } => vec![ChainActionBlob(
+                uuid_128,
+                ChainAction::Reveal {
+                    table_id,
+                    player_id: identity.clone(),
+                    bid,
+                    salt,
+                },
+            )
+            .as_blob(self.auction_game.clone(), None, None)],
+            AuctionGameCommand::End { table_id } => self.handle_auction_end(uuid_128, table_id)?,
+        };
+        // Merge blobs with identity blobs
+        blobs.extend(identity_blobs);
+        let tx = BlobTransaction::new(identity, blobs);
+        self.bus.send(tx)?;
+        Ok(())
+    }
+
+    fn handle_auction_end(&mut self, uuid_128: u128, table_id: TableId) -> Result<Vec<Blob>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let Some(auction) = self.get_auction_game().auctions.get(&table_id) else {
+            anyhow::bail!("No auction running at table {}", table_id);
+        };
+        // Pre-chain validation
+        if !auction.is_over(now) {
+            anyhow::bail!("Bids are still pending");
+        }
+        let final_results = auction.final_results()?;
+
+        Ok(vec![
+            ChainActionBlob(uuid_128, ChainAction::Done { table_id }).as_blob(
+                self.auction_game.clone(),
+                None,
+                Some(vec![BlobIndex(1)]),
+            ),
+            GameActionBlob::end_minigame(
+                uuid_128,
+                table_id,
+                MinigameResult::new(self.auction_game.clone(), &final_results),
+            )
+            .as_blob(self.board_game.clone(), Some(BlobIndex(0)), None),
+        ])
+    }
+
+    /// Settles auctions nobody closed, so that tables don't stall.
+    pub(super) async fn auction_game_on_tick(&mut self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let over: Vec<TableId> = self
+            .get_auction_game()
+            .auctions
+            .iter()
+            .filter(|(_, auction)| auction.is_over(now))
+            .map(|(table_id, _)| *table_id)
+            .collect();
+        for table_id in over {
+            if self.end_recently_sent(self.auction_game.clone(), table_id) {
+                continue;
+            }
+            let uuid = uuid::Uuid::new_v4();
+            let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
+            let mut blobs = self.handle_auction_end(uuid.as_u128(), table_id)?;
+            blobs.push(self.create_backend_identity_blob(uuid, "EndMinigame")?);
+            self.bus.send(BlobTransaction::new(identity, blobs))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn auction_table_id(&self, blob_tx: &BlobTransaction) -> Option<TableId> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| blob.contract_name == self.auction_game)
+            .find_map(|blob| {
+                StructuredBlobData::<ChainActionBlob>::try_from(blob.data.clone()).ok()
+            })
+            .map(|data| data.parameters.1.table_id())
+    }
+
+    pub(super) fn broadcast_auction_state(
+        &mut self,
+        table_id: TableId,
+        events: Vec<ChainEvent>,
+    ) -> Result<()> {
+        let state = self.get_auction_game().auctions.get(&table_id).cloned();
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::AuctionGame(AuctionGameEvent::StateUpdated {
+                table_id,
+                state,
+                events,
+            }),
+        })?;
+        Ok(())
+    }
+}
@@ -1,41 +1,90 @@
-use anyhow::{bail, Result};
-use board_game::{
-    game::{MinigameResult, PlayerMinigameResult},
-    GameActionBlob,
-};
+use anyhow::{anyhow, bail, Result};
+use board_game::GameActionBlob;
 use crash_game::{
-    ChainAction, ChainActionBlob, ChainEvent, GameState, MinigameState, ServerAction,
+    fairness, multiplier, multiplier::Multiplier, ChainAction, ChainActionBlob, ChainEvent,
+    GameState, MinigameState, Round, RoundKey, RoundSummary, Seat, ServerAction, ServerEvent,
 };
 use hyle_modules::bus::BusClientSender;
 use hyle_modules::modules::websocket::WsBroadcastMessage;
-use rand;
+use minigame_interface::{Amount, BoardProtocol, MinigameResult};
 use sdk::verifiers::Secp256k1Blob;
-use sdk::{Blob, BlobIndex, BlobTransaction, ContractAction, Identity};
+use sdk::{
+    Blob, BlobIndex, BlobTransaction, ContractAction, ContractName, Identity, StructuredBlobData,
+};
 use secp256k1::Message;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use smt_token::SmtTokenAction;
+use std::collections::BTreeMap;
 use std::ops::DerefMut;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
 use tracing::info;
 use uuid;
 
 use crate::{proving::CrashGameExecutor, OutboundWebsocketMessage};
 
+/// How long the crash point of a standalone round is shown before the next round is set up.
+const STANDALONE_RESULTS_MS: u128 = 5_000;
+
 // Message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum CrashGameCommand {
-    CashOut { player_id: Identity },
-    End,
+    CashOut {
+        key: RoundKey,
+        player_id: Identity,
+    },
+    SetAutoCashOut {
+        key: RoundKey,
+        player_id: Identity,
+        multiplier: Option<Multiplier>,
+    },
+    PlaceBet {
+        key: RoundKey,
+        player_id: Identity,
+        amount: Amount,
+    },
+    JoinTable {
+        player_id: Identity,
+        name: String,
+        deposit: Amount,
+    },
+    LeaveTable {
+        player_id: Identity,
+    },
+    End {
+        key: RoundKey,
+    },
+    // Answered with the history, nothing is sent on-chain.
+    GetHistory,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum CrashGameEvent {
+    // Rounds are sent one at a time, the state can't be keyed by round in JSON.
     StateUpdated {
-        state: Option<GameState>,
+        key: RoundKey,
+        round: Option<Round>,
+        seats: BTreeMap<Identity, Seat>,
         events: Vec<ChainEvent>,
     },
+    Tick {
+        key: RoundKey,
+        multiplier: Multiplier,
+        elapsed_ms: u64,
+        server_time: u128,
+    },
+    // Settled rounds, oldest first, sent on request and whenever a round ends.
+    History {
+        history: Vec<RoundSummary>,
+    },
+    // Only meant for `player_id`, whose bet was not sent on-chain.
+    BetRejected {
+        player_id: Identity,
+        event: ServerEvent,
+    },
 }
 
 impl super::RollupExecutor {
@@ -61,10 +110,54 @@ impl super::RollupExecutor {
     ) -> Result<()> {
         let uuid_128: u128 = uuid::Uuid::parse_str(uuid)?.as_u128();
         let mut blobs = match event {
-            CrashGameCommand::CashOut { player_id } => {
-                self.handle_cash_out(uuid_128, player_id).await
+            CrashGameCommand::CashOut { key, player_id } => {
+                self.handle_cash_out(uuid_128, key, player_id).await
+            }
+            CrashGameCommand::SetAutoCashOut {
+                key,
+                player_id,
+                multiplier,
+            } => Ok(vec![ChainActionBlob(
+                uuid_128,
+                key,
+                ChainAction::SetAutoCashOut {
+                    player_id,
+                    multiplier,
+                },
+            )
+            .as_blob(self.crash_game.clone(), None, None)]),
+            CrashGameCommand::PlaceBet {
+                key,
+                player_id,
+                amount,
+            } => self.handle_place_bet(uuid_128, key, player_id, amount),
+            CrashGameCommand::JoinTable {
+                player_id,
+                name,
+                deposit,
+            } => Ok(vec![
+                SmtTokenAction::Transfer {
+                    sender: player_id.clone(),
+                    recipient: Identity::new(self.crash_game.clone().0),
+                    amount: deposit,
+                }
+                .as_blob(ContractName::new("oranj"), None, None),
+                ChainActionBlob(
+                    uuid_128,
+                    RoundKey::standalone(),
+                    ChainAction::JoinTable {
+                        player_id,
+                        name,
+                        deposit,
+                    },
+                )
+                .as_blob(self.crash_game.clone(), None, None),
+            ]),
+            CrashGameCommand::LeaveTable { player_id } => {
+                self.handle_leave_table(uuid_128, player_id)
             }
-            CrashGameCommand::End => self.handle_end(uuid_128).await,
+            CrashGameCommand::End { key } => self.handle_end(uuid_128, key).await,
+            CrashGameCommand::GetHistory => return self.broadcast_crash_history(),
         }?;
         // Merge blobs with identity blobs
         blobs.extend(identity_blobs);
@@ -74,12 +167,22 @@ impl super::RollupExecutor {
     }
 
     // Pre-chain validation and transaction submission
-    async fn handle_cash_out(&mut self, uuid_128: u128, player_id: Identity) -> Result<Vec<Blob>> {
+    async fn handle_cash_out(
+        &mut self,
+        uuid_128: u128,
+        key: RoundKey,
+        player_id: Identity,
+    ) -> Result<Vec<Blob>> {
         // Pre-chain validation
-        let multiplier = self.get_crash_game().minigame_backend.current_multiplier;
+        let multiplier = self
+            .get_crash_game()
+            .round(&key)?
+            .minigame_backend
+            .current_multiplier;
 
         Ok(vec![ChainActionBlob(
             uuid_128,
+            key,
             ChainAction::CashOut {
                 player_id,
                 multiplier,
@@ -88,41 +191,102 @@ impl super::RollupExecutor {
         .as_blob(self.crash_game.clone(), None, None)])
     }
 
-    async fn handle_end(&mut self, uuid_128: u128) -> Result<Vec<Blob>> {
+    fn handle_place_bet(
+        &mut self,
+        uuid_128: u128,
+        key: RoundKey,
+        player_id: Identity,
+        amount: Amount,
+    ) -> Result<Vec<Blob>> {
+        // Pre-chain validation, the offending client is told why
+        let bet_error = self.get_crash_game().bet_error(&key, &player_id, amount)?;
+        if let Some(event) = bet_error {
+            self.bus.send(WsBroadcastMessage {
+                message: OutboundWebsocketMessage::CrashGame(CrashGameEvent::BetRejected {
+                    player_id,
+                    event: event.clone(),
+                }),
+            })?;
+            bail!("Invalid bet: {:?}", event);
+        }
+
+        Ok(vec![ChainActionBlob(
+            uuid_128,
+            key,
+            ChainAction::PlaceBet { player_id, amount },
+        )
+        .as_blob(self.crash_game.clone(), None, None)])
+    }
+
+    fn handle_leave_table(&mut self, uuid_128: u128, player_id: Identity) -> Result<Vec<Blob>> {
+        let Some(balance) = self
+            .get_crash_game()
+            .seats
+            .get(&player_id)
+            .map(|seat| seat.balance)
+        else {
+            bail!("Player not seated");
+        };
+        let mut blobs = vec![];
+        // Same withdrawal as the contract checks.
+        if balance > 0 {
+            blobs.push(
+                SmtTokenAction::Transfer {
+                    sender: Identity::new(self.crash_game.clone().0),
+                    recipient: player_id.clone(),
+                    amount: balance,
+                }
+                .as_blob(ContractName::new("oranj"), None, None),
+            );
+        }
+        blobs.push(
+            ChainActionBlob(
+                uuid_128,
+                RoundKey::standalone(),
+                ChainAction::LeaveTable { player_id },
+            )
+            .as_blob(self.crash_game.clone(), None, None),
+        );
+        Ok(blobs)
+    }
+
+    async fn handle_end(&mut self, uuid_128: u128, key: RoundKey) -> Result<Vec<Blob>> {
         // Pre-chain validation
-        if self.get_crash_game().minigame_verifiable.state != MinigameState::Crashed {
+        let round = self.get_crash_game().round(&key)?.clone();
+        if round.minigame_verifiable.state != MinigameState::Crashed {
             bail!("Game is still running");
         }
 
+        // Standalone rounds settle on their own, without a board to report to.
+        let Some(board) = key.board.clone() else {
+            return Ok(vec![ChainActionBlob(uuid_128, key, ChainAction::Done)
+                .as_blob(self.crash_game.clone(), None, None)]);
+        };
+
         // Get end results from server-side state
-        let final_results = self.get_crash_game().get_end_results()?;
+        let final_results = round.minigame_verifiable.get_end_results()?;
+        let table_id = key.table_id;
 
         Ok(vec![
-            ChainActionBlob(uuid_128, ChainAction::Done).as_blob(
+            ChainActionBlob(uuid_128, key, ChainAction::Done).as_blob(
                 self.crash_game.clone(),
                 None,
                 Some(vec![BlobIndex(1)]),
             ),
-            GameActionBlob(
+            GameActionBlob::end_minigame(
                 uuid_128,
-                board_game::game::GameAction::EndMinigame {
-                    result: MinigameResult {
-                        contract_name: self.crash_game.clone(),
-                        player_results: final_results
-                            .iter()
-                            .map(|r| PlayerMinigameResult {
-                                player_id: r.0.clone(),
-                                coins_delta: r.1,
-                            })
-                            .collect(),
-                    },
-                },
+                table_id,
+                MinigameResult::new(self.crash_game.clone(), &final_results),
             )
-            .as_blob(self.board_game.clone(), Some(BlobIndex(0)), None),
+            .as_blob(board, Some(BlobIndex(0)), None),
         ])
     }
 
-    fn create_backend_identity_blob(&self, uuid: uuid::Uuid, data_to_sign: &str) -> Result<Blob> {
+    pub(super) fn create_backend_identity_blob(
+        &self,
+        uuid: uuid::Uuid,
+        data_to_sign: &str,
+    ) -> Result<Blob> {
         let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
         let data = format!("{}:{}", uuid, data_to_sign).as_bytes().to_vec();
         let mut hasher = Sha256::new();
@@ -141,15 +305,77 @@ impl super::RollupExecutor {
         .as_blob())
     }
 
+    /// The chain element claimed by the round at `seed_index`, which decides its crash point.
+    fn crash_seed(&mut self, seed_index: u64) -> Result<[u8; 32]> {
+        let state = self.get_crash_game();
+        let generation = state.chain_generation;
+        let chain_length = state.chain_length;
+        let pending: Vec<(u32, u64)> = state
+            .rounds
+            .values()
+            .map(|round| round.minigame_verifiable.seed_index)
+            .filter(|index| !state.revealed_seeds.contains_key(index))
+            .map(|index| (generation, index))
+            .collect();
+        self.crash_seeds.retain(|key, _| pending.contains(key));
+        if let Some(seed) = self.crash_seeds.get(&(generation, seed_index)) {
+            return Ok(*seed);
+        }
+        let depth = u32::try_from(seed_index)
+            .ok()
+            .and_then(|index| chain_length.checked_sub(index))
+            .ok_or_else(|| anyhow!("Crash hash chain is exhausted"))?;
+        let seed = fairness::chain_end(self.crypto.crash_chain_seed(generation), depth);
+        self.crash_seeds.insert((generation, seed_index), seed);
+        Ok(seed)
+    }
+
+    /// Whether the crash hash chain runs low, in which case no standalone round is started
+    /// so that the pending ones settle and the chain can be rotated.
+    fn crash_chain_low(&mut self) -> bool {
+        let state = self.get_crash_game();
+        state.chain_claimed + crate::CRASH_CHAIN_ROTATION_MARGIN >= state.chain_length as u64
+    }
+
+    /// Commits to the next hash chain once the current one runs low and no round waits for its seed.
+    fn rotate_crash_chain_on_tick(&mut self) -> Result<()> {
+        if !self.crash_chain_low() || self.get_crash_game().seeds_pending() {
+            return Ok(());
+        }
+        let recently_sent = self
+            .last_crash_rotation
+            .is_some_and(|last| last.elapsed().as_secs() <= 10);
+        if recently_sent {
+            return Ok(());
+        }
+        let generation = self.get_crash_game().chain_generation + 1;
+        let tx = self.create_crash_backend_tx(
+            RoundKey::standalone(),
+            ChainAction::RotateChain {
+                head: self.crypto.crash_chain_head(generation),
+                length: crate::CRASH_CHAIN_LENGTH,
+            },
+        )?;
+        self.last_crash_rotation = Some(Instant::now());
+        self.bus.send(tx)?;
+        Ok(())
+    }
+
     // Server-side state management
-    fn create_crash_backend_tx(&self, action: ChainAction) -> Result<BlobTransaction> {
+    fn create_crash_backend_tx(
+        &self,
+        key: RoundKey,
+        action: ChainAction,
+    ) -> Result<BlobTransaction> {
         let uuid = uuid::Uuid::new_v4();
         let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
         let identity_blob = self.create_backend_identity_blob(
             uuid,
             match action {
                 ChainAction::Start { .. } => "Start",
+                ChainAction::StartRound { .. } => "StartRound",
                 ChainAction::Crash { .. } => "Crash",
+                ChainAction::RotateChain { .. } => "RotateChain",
                 _ => unreachable!(),
             },
         )?;
@@ -157,7 +383,7 @@ impl super::RollupExecutor {
             identity.clone(),
             vec![
                 identity_blob,
-                ChainActionBlob(uuid.as_u128(), action).as_blob(
+                ChainActionBlob(uuid.as_u128(), key, action).as_blob(
                     self.crash_game.clone(),
                     None,
                     None,
@@ -166,78 +392,197 @@ impl super::RollupExecutor {
         ))
     }
 
+    /// Backend-sent Done transactions are only retried every few seconds, per round.
+    fn crash_end_recently_sent(&mut self, key: &RoundKey) -> bool {
+        let recently_sent = self
+            .last_crash_end
+            .get(key)
+            .is_some_and(|last| last.elapsed().as_secs() <= 10);
+        if !recently_sent {
+            self.last_crash_end.insert(key.clone(), Instant::now());
+        }
+        recently_sent
+    }
+
+    /// Keeps the standalone table going while players are seated.
+    fn standalone_on_tick(&mut self) -> Result<()> {
+        let recently_sent = self
+            .last_crash_start
+            .is_some_and(|last| last.elapsed().as_secs() <= 10);
+        if recently_sent || self.crash_chain_low() || !self.get_crash_game().standalone_round_due()
+        {
+            return Ok(());
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let tx = self.create_crash_backend_tx(
+            RoundKey::standalone(),
+            ChainAction::StartRound { time: now as u64 },
+        )?;
+        self.last_crash_start = Some(Instant::now());
+        self.bus.send(tx)?;
+        Ok(())
+    }
+
     pub(super) async fn crash_game_on_tick(&mut self) -> Result<()> {
-        let state = self.get_crash_game();
+        self.rotate_crash_chain_on_tick()?;
+        self.standalone_on_tick()?;
 
-        if state.minigame_verifiable.state == MinigameState::WaitingForStart {
+        let keys: Vec<RoundKey> = self.get_crash_game().rounds.keys().cloned().collect();
+        for key in keys {
+            self.crash_round_on_tick(key).await?;
+        }
+        Ok(())
+    }
+
+    async fn crash_round_on_tick(&mut self, key: RoundKey) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let round = self.get_crash_game().round(&key)?.clone();
+
+        if round.minigame_verifiable.state == MinigameState::WaitingForStart {
             // After a while start
-            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
-            if now.saturating_sub(state.minigame_backend.game_setup_time.unwrap()) > 10_000 {
-                self.bus
-                    .send(self.create_crash_backend_tx(ChainAction::Start { time: now as u64 })?)?;
-                return Ok(());
+            if now.saturating_sub(round.minigame_backend.game_setup_time.unwrap_or(now)) > 10_000 {
+                self.bus.send(
+                    self.create_crash_backend_tx(key, ChainAction::Start { time: now as u64 })?,
+                )?;
             }
-        } else if state.minigame_verifiable.state == MinigameState::Crashed {
-            // Auto-end the game after a while to unstuck players
-            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
-            if now.saturating_sub(state.minigame_backend.game_start_time.unwrap()) > 60_000 {
+            return Ok(());
+        } else if round.minigame_verifiable.state == MinigameState::Crashed {
+            // Auto-end the game after a while to unstuck players, standalone rounds right after the crash
+            let ended = if key.board.is_none() {
+                // The last tick before the crash, or the start if it crashed right away.
+                let crashed_at = round
+                    .minigame_backend
+                    .current_time
+                    .or(round.minigame_backend.game_start_time)
+                    .unwrap_or_default();
+                now.saturating_sub(crashed_at) > STANDALONE_RESULTS_MS
+            } else {
+                now.saturating_sub(round.minigame_backend.game_start_time.unwrap_or_default())
+                    > 60_000
+            };
+            if ended && !self.crash_end_recently_sent(&key) {
                 let uuid = uuid::Uuid::new_v4();
                 let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
-                let mut blobs = self.handle_end(uuid.as_u128()).await?;
+                let mut blobs = self.handle_end(uuid.as_u128(), key).await?;
                 blobs.push(self.create_backend_identity_blob(uuid, "EndMinigame")?);
                 self.bus.send(BlobTransaction::new(identity, blobs))?;
-                return Ok(());
             }
             return Ok(());
         }
 
-        if state.minigame_verifiable.state != MinigameState::Running {
+        if round.minigame_verifiable.state != MinigameState::Running {
             return Ok(());
         }
 
-        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
-        let delta = now.saturating_sub(state.minigame_backend.current_time.unwrap());
-        state.minigame_backend.current_time = Some(now);
-        let elapsed_ms = now.saturating_sub(state.minigame_backend.game_start_time.unwrap());
+        // The crash point was committed to before the round, we only reveal it.
+        let seed = self.crash_seed(round.minigame_verifiable.seed_index)?;
+        let crash_point = fairness::crash_point(&seed);
 
-        // We don't actually send server events for now.
-        let _events = state.process_server_action(ServerAction::Update {
-            current_time: elapsed_ms as u64,
-        })?;
-
-        // Crash probability calculation: from 1% to 50% after 8 seconds (and staying there)
-        let elapsed_secs = elapsed_ms as f64 / 1000.0;
-        let crash_probability = 0.01 + (0.49 * (elapsed_secs / 8.0).min(1.0));
-
-        // Instant probability over delta ms
-        let crash_probability = crash_probability * (delta as f64 / 1000.0);
+        let elapsed_ms = now.saturating_sub(round.minigame_backend.game_start_time.unwrap()) as u64;
+        let state = self.get_crash_game();
+        let mut events = state.process_server_action(
+            &key,
+            ServerAction::Update {
+                current_time: elapsed_ms,
+            },
+        )?;
+        // The curve stops at the crash point, nobody can cash out above it while the Crash lands.
+        let round = state.round_mut(&key)?;
+        round.minigame_backend.current_time = Some(now);
+        round.minigame_backend.current_multiplier =
+            round.minigame_backend.current_multiplier.min(crash_point);
+        let current_multiplier = round.minigame_backend.current_multiplier;
+        for event in &mut events {
+            if let ServerEvent::MultiplierUpdated { multiplier } = event {
+                *multiplier = current_multiplier;
+            }
+        }
 
         info!(
-            "Updating game state - {}, {}",
-            elapsed_ms, crash_probability
+            "Updating game state of {:?} - {}, {}x",
+            key,
+            elapsed_ms,
+            multiplier::format(current_multiplier)
         );
 
-        let state = state.clone();
-
-        if rand::random::<f64>() < crash_probability {
-            self.bus
-                .send(self.create_crash_backend_tx(ChainAction::Crash {
-                    final_multiplier: state.minigame_backend.current_multiplier,
-                })?)?;
+        let crashing = current_multiplier >= crash_point;
+        if crashing {
+            self.bus.send(self.create_crash_backend_tx(
+                key.clone(),
+                ChainAction::Crash {
+                    final_multiplier: crash_point,
+                    seed,
+                },
+            )?)?;
         }
 
-        self.broadcast_state_update(state, vec![])?;
+        // Clients follow the curve from ticks, the full state is only sent when it changes on-chain.
+        // Ticks are throttled separately from the game loop, except for the last one.
+        let tick_sent_recently = self
+            .last_crash_tick
+            .get(&key)
+            .is_some_and(|last| last.elapsed() < self.crash_tick_interval);
+        if tick_sent_recently && !crashing {
+            return Ok(());
+        }
+        self.last_crash_tick.insert(key.clone(), Instant::now());
+        for event in events {
+            if let ServerEvent::MultiplierUpdated { multiplier } = event {
+                self.bus.send(WsBroadcastMessage {
+                    message: OutboundWebsocketMessage::CrashGame(CrashGameEvent::Tick {
+                        key: key.clone(),
+                        multiplier,
+                        elapsed_ms,
+                        server_time: now,
+                    }),
+                })?;
+            }
+        }
         Ok(())
     }
 
-    // Helper methods
-    fn broadcast_state_update(&mut self, state: GameState, events: Vec<ChainEvent>) -> Result<()> {
+    pub(super) fn crash_round_key(&self, blob_tx: &BlobTransaction) -> Option<RoundKey> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| blob.contract_name == self.crash_game)
+            .find_map(|blob| {
+                StructuredBlobData::<ChainActionBlob>::try_from(blob.data.clone()).ok()
+            })
+            .map(|data| data.parameters.1)
+    }
+
+    /// Sends the round `key` along with the seats, which every round may change.
+    pub(super) fn broadcast_crash_state(
+        &mut self,
+        key: RoundKey,
+        events: Vec<ChainEvent>,
+    ) -> Result<()> {
+        let state = self.get_crash_game();
+        let round = state.rounds.get(&key).cloned();
+        let seats = state.seats.clone();
         self.bus.send(WsBroadcastMessage {
             message: OutboundWebsocketMessage::CrashGame(CrashGameEvent::StateUpdated {
-                state: Some(state),
-                events,
+                key,
+                round,
+                seats,
+                events: events.clone(),
             }),
         })?;
+        if events
+            .iter()
+            .any(|event| matches!(event, ChainEvent::MinigameEnded { .. }))
+        {
+            self.broadcast_crash_history()?;
+        }
+        Ok(())
+    }
+
+    fn broadcast_crash_history(&mut self) -> Result<()> {
+        let history = self.get_crash_game().history.iter().cloned().collect();
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::CrashGame(CrashGameEvent::History { history }),
+        })?;
         Ok(())
     }
 }
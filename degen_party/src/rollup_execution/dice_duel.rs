@@ -0,0 +1,168 @@
+use anyhow::Result;
+use board_game::{game::TableId, GameActionBlob};
+use dice_duel::{Bracket, ChainAction, ChainActionBlob, ChainEvent, GameState};
+use hyle_modules::bus::BusClientSender;
+use hyle_modules::modules::websocket::WsBroadcastMessage;
+use minigame_interface::{BoardProtocol, MinigameResult};
+use sdk::{Blob, BlobIndex, BlobTransaction, ContractAction, Identity, StructuredBlobData};
+use serde::{Deserialize, Serialize};
+use std::ops::DerefMut;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{proving::DiceDuelExecutor, OutboundWebsocketMessage};
+
+// Message types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum DiceDuelCommand {
+    End { table_id: TableId },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum DiceDuelEvent {
+    StateUpdated {
+        table_id: TableId,
+        state: Option<Bracket>,
+        events: Vec<ChainEvent>,
+    },
+}
+
+impl super::RollupExecutor {
+    pub(super) fn get_dice_duel(&mut self) -> &mut GameState {
+        let dd = self.dice_duel.clone();
+        &mut self
+            .contracts
+            .get_mut(&dd)
+            .expect("Dice duel not initialized")
+            .deref_mut()
+            .as_any_mut()
+            .downcast_mut::<DiceDuelExecutor>()
+            .expect("Dice duel state is not of the expected type")
+            .state
+    }
+
+    pub(super) async fn handle_dice_duel_message(
+        &mut self,
+        event: DiceDuelCommand,
+        identity: Identity,
+        uuid: &str,
+        identity_blobs: Vec<Blob>,
+    ) -> Result<()> {
+        let uuid_128: u128 = uuid::Uuid::parse_str(uuid)?.as_u128();
+        let mut blobs = match event {
+            DiceDuelCommand::End { table_id } => self.handle_dice_duel_end(uuid_128, table_id)?,
+        };
+        // Merge blobs with identity blobs
+        blobs.extend(identity_blobs);
+        let tx = BlobTransaction::new(identity, blobs);
+        self.bus.send(tx)?;
+        Ok(())
+    }
+
+    fn handle_dice_duel_end(&mut self, uuid_128: u128, table_id: TableId) -> Result<Vec<Blob>> {
+        let Some(bracket) = self.get_dice_duel().brackets.get(&table_id) else {
+            anyhow::bail!("No bracket running at table {}", table_id);
+        };
+        // Pre-chain validation
+        if !bracket.is_over() {
+            anyhow::bail!("The bracket is not decided yet");
+        }
+        let final_results = bracket.final_results()?;
+
+        Ok(vec![
+            ChainActionBlob(uuid_128, ChainAction::Done { table_id }).as_blob(
+                self.dice_duel.clone(),
+                None,
+                Some(vec![BlobIndex(1)]),
+            ),
+            GameActionBlob::end_minigame(
+                uuid_128,
+                table_id,
+                MinigameResult::new(self.dice_duel.clone(), &final_results),
+            )
+            .as_blob(self.board_game.clone(), Some(BlobIndex(0)), None),
+        ])
+    }
+
+    /// Plays the rounds of every bracket as they come due, then ends the minigame.
+    pub(super) async fn dice_duel_on_tick(&mut self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let due: Vec<(TableId, bool, u32)> = self
+            .get_dice_duel()
+            .brackets
+            .iter()
+            .filter(|(_, bracket)| bracket.is_over() || now >= bracket.next_round_at)
+            .map(|(table_id, bracket)| (*table_id, bracket.is_over(), bracket.round))
+            .collect();
+        for (table_id, is_over, round) in due {
+            if is_over {
+                if !self.end_recently_sent(self.dice_duel.clone(), table_id) {
+                    self.send_dice_duel_backend_tx(table_id, true)?;
+                }
+            } else if !self.round_recently_sent(table_id, round) {
+                self.send_dice_duel_backend_tx(table_id, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rounds are sent once, and retried if they did not go through after a while.
+    fn round_recently_sent(&mut self, table_id: TableId, round: u32) -> bool {
+        if let Some((sent_round, sent_at)) = self.last_duel_round.get(&table_id) {
+            if *sent_round == round && sent_at.elapsed().as_secs() <= 10 {
+                return true;
+            }
+        }
+        self.last_duel_round
+            .insert(table_id, (round, Instant::now()));
+        false
+    }
+
+    fn send_dice_duel_backend_tx(&mut self, table_id: TableId, end: bool) -> Result<()> {
+        let uuid = uuid::Uuid::new_v4();
+        let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
+        let mut blobs = if end {
+            self.handle_dice_duel_end(uuid.as_u128(), table_id)?
+        } else {
+            vec![
+                ChainActionBlob(uuid.as_u128(), ChainAction::PlayRound { table_id }).as_blob(
+                    self.dice_duel.clone(),
+                    None,
+                    None,
+                ),
+            ]
+        };
+        let data_to_sign = if end { "EndMinigame" } else { "PlayRound" };
+        blobs.push(self.create_backend_identity_blob(uuid, data_to_sign)?);
+        self.bus.send(BlobTransaction::new(identity, blobs))?;
+        Ok(())
+    }
+
+    pub(super) fn dice_duel_table_id(&self, blob_tx: &BlobTransaction) -> Option<TableId> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| blob.contract_name == self.dice_duel)
+            .find_map(|blob| {
+                StructuredBlobData::<ChainActionBlob>::try_from(blob.data.clone()).ok()
+            })
+            .map(|data| data.parameters.1.table_id())
+    }
+
+    pub(super) fn broadcast_dice_duel_state(
+        &mut self,
+        table_id: TableId,
+        events: Vec<ChainEvent>,
+    ) -> Result<()> {
+        let state = self.get_dice_duel().brackets.get(&table_id).cloned();
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::DiceDuel(DiceDuelEvent::StateUpdated {
+                table_id,
+                state,
+                events,
+            }),
+        })?;
+        Ok(())
+    }
+}
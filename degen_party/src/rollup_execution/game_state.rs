@@ -1,9 +1,12 @@
 use anyhow::{bail, Result};
 use board_game::{
-    game::{GameAction as BoardGameAction, GameEvent, GamePhase, GameState},
+    game::{
+        AmountDelta, GameAction as BoardGameAction, GameEvent, GamePhase, MinigameSetup, Table,
+        TableId,
+    },
     GameActionBlob,
 };
-use crash_game::ChainActionBlob;
+use crash_game::{ChainActionBlob, RoundKey};
 use hyle_modules::{bus::BusClientSender, modules::websocket::WsBroadcastMessage};
 use sdk::{
     verifiers::Secp256k1Blob, Blob, BlobIndex, BlobTransaction, ContractAction, ContractName,
@@ -21,7 +24,10 @@ use crate::{proving::BoardGameExecutor, OutboundWebsocketMessage};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum GameStateCommand {
-    SubmitAction { action: BoardGameAction },
+    SubmitAction {
+        table_id: TableId,
+        action: BoardGameAction,
+    },
     SendState,
 }
 
@@ -29,14 +35,20 @@ pub enum GameStateCommand {
 #[serde(tag = "type", content = "payload")]
 pub enum GameStateEvent {
     StateUpdated {
-        state: Option<GameState>,
+        table_id: TableId,
+        state: Option<Table>,
         events: Vec<GameEvent>,
         board_game: ContractName,
         crash_game: ContractName,
+        auction_game: ContractName,
+        dice_duel: ContractName,
+        resource_split: ContractName,
+        lottery: ContractName,
     },
     MinigameEnded {
+        table_id: TableId,
         contract_name: ContractName,
-        final_results: Vec<(Identity, i32)>,
+        final_results: Vec<(Identity, AmountDelta)>,
     },
 }
 
@@ -53,6 +65,10 @@ impl super::RollupExecutor {
             .state
     }
 
+    pub(super) fn get_table(&self, table_id: TableId) -> Result<&Table> {
+        self.get_board_game().table(table_id)
+    }
+
     pub(super) async fn handle_user_message(
         &mut self,
         event: GameStateCommand,
@@ -61,8 +77,8 @@ impl super::RollupExecutor {
         identity_blobs: Vec<Blob>,
     ) -> Result<()> {
         match event {
-            GameStateCommand::SubmitAction { action } => {
-                self.handle_submit_action(action, identity, uuid, identity_blobs)
+            GameStateCommand::SubmitAction { table_id, action } => {
+                self.handle_submit_action(table_id, action, identity, uuid, identity_blobs)
                     .await
             }
             GameStateCommand::SendState => self.handle_send_state().await,
@@ -71,6 +87,7 @@ impl super::RollupExecutor {
 
     async fn handle_submit_action(
         &mut self,
+        table_id: TableId,
         action: BoardGameAction,
         identity: Identity,
         uuid: &str,
@@ -87,20 +104,27 @@ impl super::RollupExecutor {
                 bail!("EndMinigame cannot be called directly");
             }
             BoardGameAction::StartMinigame { .. } => {
-                match &self.get_board_game().phase {
+                let table = self.get_table(table_id)?;
+                match &table.phase {
                     GamePhase::StartMinigame(minigame_type)
                     | GamePhase::FinalMinigame(minigame_type) => {
-                        if minigame_type != &self.crash_game {
-                            bail!("Not the right minigame");
+                        if !self.registered_minigames().contains(minigame_type) {
+                            bail!("Unknown minigame {}", minigame_type);
                         }
                         tracing::warn!("Starting minigame: {:?}", minigame_type);
+                        let players = table.get_minigame_setup();
+                        // The dice duel bracket is seeded from the table's dice.
+                        let seed =
+                            (minigame_type == &self.dice_duel).then(|| table.minigame_seed());
                         // TODO ensure we are synchronized correctly.
                         blobs.push(
                             GameActionBlob(
                                 uuid_128,
+                                table_id,
                                 BoardGameAction::StartMinigame {
                                     minigame: minigame_type.clone(),
-                                    players: self.get_board_game().get_minigame_setup(),
+                                    players: players.clone(),
+                                    seed,
                                 },
                             )
                             .as_blob(
@@ -109,21 +133,12 @@ impl super::RollupExecutor {
                                 None,
                             ),
                         );
-                        blobs.push(
-                            ChainActionBlob(
-                                uuid_128,
-                                crash_game::ChainAction::InitMinigame {
-                                    players: self.get_board_game().get_minigame_setup(),
-                                    time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
-                                        as u64,
-                                },
-                            )
-                            .as_blob(
-                                self.crash_game.clone(),
-                                None,
-                                Some(vec![BlobIndex(0)]),
-                            ),
-                        );
+                        blobs.push(self.minigame_init_blob(
+                            minigame_type,
+                            uuid_128,
+                            table_id,
+                            players,
+                        )?);
                     }
                     _ => {
                         bail!("Not ready to start a game");
@@ -131,17 +146,71 @@ impl super::RollupExecutor {
                 }
             }
             BoardGameAction::EndGame => {
-                let tx = self.create_backend_tx(action.clone())?;
-                self.bus.send(tx)?;
+                let tx = self.create_backend_tx(table_id, action.clone())?;
+                let mut blobs = tx.blobs.clone();
+                // Unfinished games give the deposits back to the players still in.
+                for (player_id, amount) in self.get_table(table_id)?.refunds() {
+                    blobs.push(
+                        SmtTokenAction::Transfer {
+                            sender: Identity::new(self.board_game.clone().0),
+                            recipient: player_id,
+                            amount,
+                        }
+                        .as_blob(ContractName::new("oranj"), None, None),
+                    );
+                }
+                self.bus
+                    .send(BlobTransaction::new(tx.identity.clone(), blobs))?;
                 return Ok(());
             }
-            BoardGameAction::Initialize { .. } => {
+            BoardGameAction::LeaveGame => {
+                let table = self.get_table(table_id)?;
+                if table.phase == GamePhase::Registration {
+                    if let Some(player) = table.players.iter().find(|p| p.id == identity) {
+                        blobs.push(
+                            SmtTokenAction::Transfer {
+                                sender: Identity::new(self.board_game.clone().0),
+                                recipient: identity.clone(),
+                                amount: player.deposit,
+                            }
+                            .as_blob(
+                                ContractName::new("oranj"),
+                                None,
+                                None,
+                            ),
+                        );
+                    }
+                }
+                blobs.push(GameActionBlob(uuid_128, table_id, action.clone()).as_blob(
+                    self.board_game.clone(),
+                    None,
+                    None,
+                ));
+            }
+            BoardGameAction::Initialize {
+                minigames, config, ..
+            } => {
+                // By default, every minigame the backend can run is part of the game.
+                let registered = self.registered_minigames();
+                let minigames = if minigames.is_empty() {
+                    registered.into_iter().map(|m| m.0).collect()
+                } else {
+                    for minigame in minigames {
+                        if !registered.contains(&ContractName::new(minigame.clone())) {
+                            bail!("Unknown minigame {}", minigame);
+                        }
+                    }
+                    minigames.clone()
+                };
+                let seed_commitment = Sha256::digest(self.board_seed(table_id, uuid_128)).into();
                 blobs.push(
                     GameActionBlob(
                         uuid_128,
+                        table_id,
                         BoardGameAction::Initialize {
-                            minigames: vec![self.crash_game.clone().0],
-                            random_seed: uuid_128 as u64,
+                            minigames,
+                            seed_commitment,
+                            config: config.clone(),
                         },
                     )
                     .as_blob(self.board_game.clone(), None, None),
@@ -152,18 +221,18 @@ impl super::RollupExecutor {
                     SmtTokenAction::Transfer {
                         sender: identity.clone(),
                         recipient: Identity::new(self.board_game.clone().0),
-                        amount: *deposit as u128,
+                        amount: *deposit,
                     }
                     .as_blob(ContractName::new("oranj"), None, None),
                 );
-                blobs.push(GameActionBlob(uuid_128, action.clone()).as_blob(
+                blobs.push(GameActionBlob(uuid_128, table_id, action.clone()).as_blob(
                     self.board_game.clone(),
                     None,
                     None,
                 ));
             }
             _ => {
-                blobs.push(GameActionBlob(uuid_128, action.clone()).as_blob(
+                blobs.push(GameActionBlob(uuid_128, table_id, action.clone()).as_blob(
                     self.board_game.clone(),
                     None,
                     None,
@@ -183,7 +252,84 @@ impl super::RollupExecutor {
         Ok(())
     }
 
-    fn create_backend_tx(&self, action: BoardGameAction) -> Result<BlobTransaction> {
+    /// The seed committed to at Initialize. It is derived from the backend key so that it
+    /// never needs to be stored, and can't be guessed by anyone else.
+    fn board_seed(&self, table_id: TableId, nonce: u128) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.crypto.secret_key.secret_bytes());
+        hasher.update(self.board_game.0.as_bytes());
+        hasher.update(table_id.to_le_bytes());
+        hasher.update(nonce.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Minigame contracts the backend knows how to run.
+    pub(super) fn registered_minigames(&self) -> Vec<ContractName> {
+        vec![
+            self.crash_game.clone(),
+            self.auction_game.clone(),
+            self.dice_duel.clone(),
+            self.resource_split.clone(),
+            self.lottery.clone(),
+        ]
+    }
+
+    /// Builds the minigame side of a StartMinigame transaction, composed with the board blob at index 0.
+    pub(super) fn minigame_init_blob(
+        &self,
+        minigame: &ContractName,
+        uuid_128: u128,
+        table_id: TableId,
+        players: MinigameSetup,
+    ) -> Result<Blob> {
+        if minigame == &self.crash_game {
+            Ok(ChainActionBlob(
+                uuid_128,
+                RoundKey::board(self.board_game.clone(), table_id),
+                crash_game::ChainAction::InitMinigame {
+                    players,
+                    time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+                },
+            )
+            .as_blob(self.crash_game.clone(), None, Some(vec![BlobIndex(0)])))
+        } else if minigame == &self.auction_game {
+            Ok(auction_game::ChainActionBlob(
+                uuid_128,
+                auction_game::ChainAction::InitMinigame { table_id, players },
+            )
+            .as_blob(self.auction_game.clone(), None, Some(vec![BlobIndex(0)])))
+        } else if minigame == &self.dice_duel {
+            Ok(dice_duel::ChainActionBlob(
+                uuid_128,
+                dice_duel::ChainAction::InitMinigame {
+                    table_id,
+                    players,
+                    seed: self.get_table(table_id)?.minigame_seed(),
+                },
+            )
+            .as_blob(self.dice_duel.clone(), None, Some(vec![BlobIndex(0)])))
+        } else if minigame == &self.resource_split {
+            Ok(resource_split::ChainActionBlob(
+                uuid_128,
+                resource_split::ChainAction::InitMinigame { table_id, players },
+            )
+            .as_blob(self.resource_split.clone(), None, Some(vec![BlobIndex(0)])))
+        } else if minigame == &self.lottery {
+            Ok(lottery::ChainActionBlob(
+                uuid_128,
+                lottery::ChainAction::InitMinigame { table_id, players },
+            )
+            .as_blob(self.lottery.clone(), None, Some(vec![BlobIndex(0)])))
+        } else {
+            bail!("Unknown minigame {}", minigame)
+        }
+    }
+
+    fn create_backend_tx(
+        &self,
+        table_id: TableId,
+        action: BoardGameAction,
+    ) -> Result<BlobTransaction> {
         let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
         let uuid = uuid::Uuid::new_v4();
         let data = format!(
@@ -192,6 +338,8 @@ impl super::RollupExecutor {
             match action {
                 BoardGameAction::EndGame => "EndGame",
                 BoardGameAction::SpinWheel => "SpinWheel",
+                BoardGameAction::EndTurn => "EndTurn",
+                BoardGameAction::RevealSeed { .. } => "RevealSeed",
                 BoardGameAction::DistributeRewards => "DistributeRewards",
                 _ => unreachable!(),
             }
@@ -215,51 +363,125 @@ impl super::RollupExecutor {
                     &signature.to_string(),
                 )?
                 .as_blob(),
-                GameActionBlob(uuid.as_u128(), action).as_blob(self.board_game.clone(), None, None),
+                GameActionBlob(uuid.as_u128(), table_id, action).as_blob(
+                    self.board_game.clone(),
+                    None,
+                    None,
+                ),
             ],
         ))
     }
 
     pub(super) async fn board_game_on_tick(&mut self) -> Result<()> {
-        let state = self.get_board_game();
-        if state.phase == GamePhase::Betting {
-            let likely_timed_out = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
-                > state.round_started_at + 40 * 1000;
-            if likely_timed_out {
-                let tx = self.create_backend_tx(BoardGameAction::SpinWheel)?;
-                self.bus.send(tx)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let tables: Vec<(TableId, GamePhase, u128, Option<u128>)> = self
+            .get_board_game()
+            .tables
+            .iter()
+            .map(|(id, table)| {
+                (
+                    *id,
+                    table.phase.clone(),
+                    table.deadline,
+                    (!table.seed_revealed).then_some(table.seed_nonce),
+                )
+            })
+            .collect();
+        for (table_id, phase, deadline, unrevealed_nonce) in tables {
+            // The dice seed is only revealed once registration closed on-chain,
+            // so that late registrants can't grind their entropy against it.
+            if let Some(nonce) = unrevealed_nonce {
+                let started = !matches!(phase, GamePhase::Registration | GamePhase::GameOver);
+                let recently_revealed = self
+                    .last_seed_reveal
+                    .get(&table_id)
+                    .is_some_and(|last| last.elapsed().as_secs() <= 5);
+                if started && !recently_revealed {
+                    self.last_seed_reveal
+                        .insert(table_id, tokio::time::Instant::now());
+                    let seed = self.board_seed(table_id, nonce);
+                    let tx =
+                        self.create_backend_tx(table_id, BoardGameAction::RevealSeed { seed })?;
+                    self.bus.send(tx)?;
+                }
             }
-        } else if state.phase == GamePhase::RewardsDistribution {
-            // avoid spam
-            if self.last_claim_reward.elapsed().as_secs() > 10 {
-                self.last_claim_reward = tokio::time::Instant::now();
-                let tx = self.create_backend_tx(BoardGameAction::DistributeRewards)?;
-                let mut blobs = tx.blobs.clone();
-                for player in &self.get_board_game().players {
-                    blobs.push(
-                        SmtTokenAction::Transfer {
-                            sender: Identity::new(self.board_game.clone().0),
-                            recipient: player.id.clone(),
-                            amount: player.coins as u128,
-                        }
-                        .as_blob(ContractName::new("oxygen"), None, None),
-                    );
+            if phase == GamePhase::Betting {
+                let likely_timed_out = now > deadline + 10 * 1000;
+                if likely_timed_out && unrevealed_nonce.is_none() {
+                    let tx = self.create_backend_tx(table_id, BoardGameAction::SpinWheel)?;
+                    self.bus.send(tx)?;
+                }
+            } else if phase == GamePhase::BoardMoves {
+                // Move idle players along so the table doesn't stall.
+                let likely_timed_out = now > deadline + 5 * 1000;
+                if likely_timed_out {
+                    let tx = self.create_backend_tx(table_id, BoardGameAction::EndTurn)?;
+                    self.bus.send(tx)?;
+                }
+            } else if phase == GamePhase::RewardsDistribution {
+                // avoid spam
+                let recently_claimed = self
+                    .last_claim_reward
+                    .get(&table_id)
+                    .is_some_and(|last| last.elapsed().as_secs() <= 10);
+                if !recently_claimed {
+                    self.last_claim_reward
+                        .insert(table_id, tokio::time::Instant::now());
+                    let tx =
+                        self.create_backend_tx(table_id, BoardGameAction::DistributeRewards)?;
+                    let mut blobs = tx.blobs.clone();
+                    // Same payouts as the contract checks.
+                    let treasury = self.get_board_game().backend_identity.clone();
+                    for (recipient, amount) in
+                        self.get_table(table_id)?.reward_transfers(&treasury)?
+                    {
+                        blobs.push(
+                            SmtTokenAction::Transfer {
+                                sender: Identity::new(self.board_game.clone().0),
+                                recipient,
+                                amount,
+                            }
+                            .as_blob(
+                                ContractName::new("oxygen"),
+                                None,
+                                None,
+                            ),
+                        );
+                    }
+                    self.bus
+                        .send(BlobTransaction::new(tx.identity.clone(), blobs))?;
                 }
-                self.bus
-                    .send(BlobTransaction::new(tx.identity.clone(), blobs))?;
             }
         }
         Ok(())
     }
 
     async fn handle_send_state(&mut self) -> Result<()> {
-        let state = self.get_board_game();
+        let tables = self.get_board_game().tables.clone();
+        for (table_id, table) in tables {
+            self.broadcast_table_state(table_id, Some(table), vec![])?;
+        }
+        self.broadcast_lottery_jackpot()?;
+        Ok(())
+    }
+
+    pub(super) fn broadcast_table_state(
+        &mut self,
+        table_id: TableId,
+        state: Option<Table>,
+        events: Vec<GameEvent>,
+    ) -> Result<()> {
         self.bus.send(WsBroadcastMessage {
             message: OutboundWebsocketMessage::GameStateEvent(GameStateEvent::StateUpdated {
-                state: Some(state.clone()),
-                events: vec![],
+                table_id,
+                state,
+                events,
                 board_game: self.board_game.clone(),
                 crash_game: self.crash_game.clone(),
+                auction_game: self.auction_game.clone(),
+                dice_duel: self.dice_duel.clone(),
+                resource_split: self.resource_split.clone(),
+                lottery: self.lottery.clone(),
             }),
         })?;
         Ok(())
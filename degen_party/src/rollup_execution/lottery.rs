@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Result};
+use board_game::{game::TableId, GameActionBlob};
+use hyle_modules::bus::BusClientSender;
+use hyle_modules::modules::websocket::WsBroadcastMessage;
+use lottery::{fairness, ChainAction, ChainActionBlob, ChainEvent, Draw, GameState};
+use minigame_interface::{Amount, BoardProtocol, MinigameResult};
+use sdk::{BlobIndex, BlobTransaction, ContractAction, Identity, StructuredBlobData};
+use serde::{Deserialize, Serialize};
+use std::ops::DerefMut;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{proving::LotteryExecutor, OutboundWebsocketMessage};
+
+// Message types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum LotteryEvent {
+    StateUpdated {
+        table_id: TableId,
+        state: Option<Draw>,
+        jackpot: Amount,
+        events: Vec<ChainEvent>,
+    },
+    Jackpot {
+        jackpot: Amount,
+    },
+}
+
+impl super::RollupExecutor {
+    pub(super) fn get_lottery(&mut self) -> &mut GameState {
+        let lottery = self.lottery.clone();
+        &mut self
+            .contracts
+            .get_mut(&lottery)
+            .expect("Lottery not initialized")
+            .deref_mut()
+            .as_any_mut()
+            .downcast_mut::<LotteryExecutor>()
+            .expect("Lottery state is not of the expected type")
+            .state
+    }
+
+    /// The chain element claimed by the draw at `seed_index`, which picks its winners.
+    fn lottery_seed(&mut self, seed_index: u64) -> Result<[u8; 32]> {
+        let lottery = self.get_lottery();
+        let (generation, revealed, chain_length) = (
+            lottery.chain_generation,
+            lottery.chain_revealed,
+            lottery.chain_length,
+        );
+        self.lottery_seeds
+            .retain(|(g, index), _| *g == generation && *index > revealed);
+        if let Some(seed) = self.lottery_seeds.get(&(generation, seed_index)) {
+            return Ok(*seed);
+        }
+        let depth = u32::try_from(seed_index)
+            .ok()
+            .and_then(|index| chain_length.checked_sub(index))
+            .ok_or_else(|| anyhow!("Lottery hash chain is exhausted"))?;
+        let seed = fairness::chain_end(self.crypto.lottery_chain_seed(generation), depth);
+        self.lottery_seeds.insert((generation, seed_index), seed);
+        Ok(seed)
+    }
+
+    /// Commits to the next hash chain once the current one runs low and no draw is running.
+    fn rotate_lottery_chain_on_tick(&mut self) -> Result<()> {
+        let lottery = self.get_lottery();
+        let chain_low = lottery.chain_claimed + crate::LOTTERY_CHAIN_ROTATION_MARGIN
+            >= lottery.chain_length as u64;
+        if !chain_low || !lottery.draws.is_empty() {
+            return Ok(());
+        }
+        let recently_sent = self
+            .last_lottery_rotation
+            .is_some_and(|last| last.elapsed().as_secs() <= 10);
+        if recently_sent {
+            return Ok(());
+        }
+        let generation = self.get_lottery().chain_generation + 1;
+        let uuid = uuid::Uuid::new_v4();
+        let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
+        let action = ChainAction::RotateChain {
+            head: self.crypto.lottery_chain_head(generation),
+            length: crate::LOTTERY_CHAIN_LENGTH,
+        };
+        let blobs = vec![
+            self.create_backend_identity_blob(uuid, "RotateChain")?,
+            ChainActionBlob(uuid.as_u128(), action).as_blob(self.lottery.clone(), None, None),
+        ];
+        self.last_lottery_rotation = Some(tokio::time::Instant::now());
+        self.bus.send(BlobTransaction::new(identity, blobs))?;
+        Ok(())
+    }
+
+    /// Draws the lotteries whose tickets have been on display long enough, in the order
+    /// they claimed their seeds.
+    pub(super) async fn lottery_on_tick(&mut self) -> Result<()> {
+        self.rotate_lottery_chain_on_tick()?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let lottery = self.get_lottery();
+        let due: Vec<(TableId, u64)> = lottery
+            .draws
+            .iter()
+            .filter(|(_, draw)| now >= draw.draw_at && lottery.can_draw(draw.seed_index))
+            .map(|(table_id, draw)| (*table_id, draw.seed_index))
+            .collect();
+        for (table_id, seed_index) in due {
+            if self.end_recently_sent(self.lottery.clone(), table_id) {
+                continue;
+            }
+            let seed = self.lottery_seed(seed_index)?;
+            let lottery = self.get_lottery();
+            let jackpot = lottery.jackpot;
+            let Some(draw) = lottery.draws.get(&table_id) else {
+                continue;
+            };
+            let settlement = draw.settle(table_id, &seed, jackpot)?;
+
+            let uuid = uuid::Uuid::new_v4();
+            let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
+            let blobs = vec![
+                ChainActionBlob(uuid.as_u128(), ChainAction::Done { table_id, seed }).as_blob(
+                    self.lottery.clone(),
+                    None,
+                    Some(vec![BlobIndex(1)]),
+                ),
+                GameActionBlob::end_minigame(
+                    uuid.as_u128(),
+                    table_id,
+                    MinigameResult::new(self.lottery.clone(), &settlement.final_results),
+                )
+                .as_blob(self.board_game.clone(), Some(BlobIndex(0)), None),
+                self.create_backend_identity_blob(uuid, "EndMinigame")?,
+            ];
+            self.bus.send(BlobTransaction::new(identity, blobs))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn lottery_table_id(&self, blob_tx: &BlobTransaction) -> Option<TableId> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| blob.contract_name == self.lottery)
+            .find_map(|blob| {
+                StructuredBlobData::<ChainActionBlob>::try_from(blob.data.clone()).ok()
+            })
+            .and_then(|data| data.parameters.1.table_id())
+    }
+
+    pub(super) fn broadcast_lottery_state(
+        &mut self,
+        table_id: TableId,
+        events: Vec<ChainEvent>,
+    ) -> Result<()> {
+        let lottery = self.get_lottery();
+        let state = lottery.draws.get(&table_id).cloned();
+        let jackpot = lottery.jackpot;
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::Lottery(LotteryEvent::StateUpdated {
+                table_id,
+                state,
+                jackpot,
+                events,
+            }),
+        })?;
+        Ok(())
+    }
+
+    /// The jackpot is shown outside of draws too, so it is sent along with the board state.
+    pub(super) fn broadcast_lottery_jackpot(&mut self) -> Result<()> {
+        let jackpot = self.get_lottery().jackpot;
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::Lottery(LotteryEvent::Jackpot { jackpot }),
+        })?;
+        Ok(())
+    }
+}
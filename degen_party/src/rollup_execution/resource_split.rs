@@ -0,0 +1,181 @@
+use anyhow::Result;
+use board_game::{game::TableId, GameActionBlob};
+use hyle_modules::bus::BusClientSender;
+use hyle_modules::modules::websocket::WsBroadcastMessage;
+use minigame_interface::{BoardProtocol, MinigameResult};
+use resource_split::{ChainAction, ChainActionBlob, ChainEvent, Choice, GameState, Split};
+use sdk::{Blob, BlobIndex, BlobTransaction, ContractAction, Identity, StructuredBlobData};
+use serde::{Deserialize, Serialize};
+use std::ops::DerefMut;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{proving::ResourceSplitExecutor, OutboundWebsocketMessage};
+
+// Message types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ResourceSplitCommand {
+    Commit {
+        table_id: TableId,
+        commitment: [u8; 32],
+    },
+    Reveal {
+        table_id: TableId,
+        choice: Choice,
+        salt: [u8; 32],
+    },
+    End {
+        table_id: TableId,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ResourceSplitEvent {
+    StateUpdated {
+        table_id: TableId,
+        state: Option<Split>,
+        events: Vec<ChainEvent>,
+    },
+}
+
+impl super::RollupExecutor {
+    pub(super) fn get_resource_split(&mut self) -> &mut GameState {
+        let rs = self.resource_split.clone();
+        &mut self
+            .contracts
+            .get_mut(&rs)
+            .expect("Resource split not initialized")
+            .deref_mut()
+            .as_any_mut()
+            .downcast_mut::<ResourceSplitExecutor>()
+            .expect("Resource split state is not of the expected type")
+            .state
+    }
+
+    pub(super) async fn handle_resource_split_message(
+        &mut self,
+        event: ResourceSplitCommand,
+        identity: Identity,
+        uuid: &str,
+        identity_blobs: Vec<Blob>,
+    ) -> Result<()> {
+        let uuid_128: u128 = uuid::Uuid::parse_str(uuid)?.as_u128();
+        let mut blobs = match event {
+            ResourceSplitCommand::Commit {
+                table_id,
+                commitment,
+            } => vec![ChainActionBlob(
+                uuid_128,
+                ChainAction::Commit {
+                    table_id,
+                    player_id: identity.clone(),
+                    commitment,
+                },
+            )
+            .as_blob(self.resource_split.clone(), None, None)],
+            ResourceSplitCommand::Reveal {
+                table_id,
+                choice,
+                salt,
+            } => vec![ChainActionBlob(
+                uuid_128,
+                ChainAction::Reveal {
+                    table_id,
+                    player_id: identity.clone(),
+                    choice,
+                    salt,
+                },
+            )
+            .as_blob(self.resource_split.clone(), None, None)],
+            ResourceSplitCommand::End { table_id } => {
+                self.handle_resource_split_end(uuid_128, table_id)?
+            }
+        };
+        // Merge blobs with identity blobs
+        blobs.extend(identity_blobs);
+        let tx = BlobTransaction::new(identity, blobs);
+        self.bus.send(tx)?;
+        Ok(())
+    }
+
+    fn handle_resource_split_end(
+        &mut self,
+        uuid_128: u128,
+        table_id: TableId,
+    ) -> Result<Vec<Blob>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let Some(split) = self.get_resource_split().splits.get(&table_id) else {
+            anyhow::bail!("No split running at table {}", table_id);
+        };
+        // Pre-chain validation
+        if !split.is_over(now) {
+            anyhow::bail!("Choices are still pending");
+        }
+        let final_results = split.final_results()?;
+
+        Ok(vec![
+            ChainActionBlob(uuid_128, ChainAction::Done { table_id }).as_blob(
+                self.resource_split.clone(),
+                None,
+                Some(vec![BlobIndex(1)]),
+            ),
+            GameActionBlob::end_minigame(
+                uuid_128,
+                table_id,
+                MinigameResult::new(self.resource_split.clone(), &final_results),
+            )
+            .as_blob(self.board_game.clone(), Some(BlobIndex(0)), None),
+        ])
+    }
+
+    /// Settles splits nobody closed, so that tables don't stall.
+    pub(super) async fn resource_split_on_tick(&mut self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let over: Vec<TableId> = self
+            .get_resource_split()
+            .splits
+            .iter()
+            .filter(|(_, split)| split.is_over(now))
+            .map(|(table_id, _)| *table_id)
+            .collect();
+        for table_id in over {
+            if self.end_recently_sent(self.resource_split.clone(), table_id) {
+                continue;
+            }
+            let uuid = uuid::Uuid::new_v4();
+            let identity = Identity::new(format!("{}@secp256k1", self.crypto.public_key));
+            let mut blobs = self.handle_resource_split_end(uuid.as_u128(), table_id)?;
+            blobs.push(self.create_backend_identity_blob(uuid, "EndMinigame")?);
+            self.bus.send(BlobTransaction::new(identity, blobs))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn resource_split_table_id(&self, blob_tx: &BlobTransaction) -> Option<TableId> {
+        blob_tx
+            .blobs
+            .iter()
+            .filter(|blob| blob.contract_name == self.resource_split)
+            .find_map(|blob| {
+                StructuredBlobData::<ChainActionBlob>::try_from(blob.data.clone()).ok()
+            })
+            .map(|data| data.parameters.1.table_id())
+    }
+
+    pub(super) fn broadcast_resource_split_state(
+        &mut self,
+        table_id: TableId,
+        events: Vec<ChainEvent>,
+    ) -> Result<()> {
+        let state = self.get_resource_split().splits.get(&table_id).cloned();
+        self.bus.send(WsBroadcastMessage {
+            message: OutboundWebsocketMessage::ResourceSplit(ResourceSplitEvent::StateUpdated {
+                table_id,
+                state,
+                events,
+            }),
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+import { reactive } from "vue";
+import { BaseWebSocketService } from "../utils/base-websocket";
+import { gameState } from "./game_data";
+
+export interface AuctionChainEvent {
+    AuctionSettled?: {
+        winner: string | null;
+        winning_bid: number;
+        pot: number;
+    };
+    MinigameEnded?: {
+        final_results: Array<[string, number]>;
+    };
+}
+
+export interface Bidder {
+    id: string;
+    name: string;
+    ante: number;
+    coins: number;
+    commitment: number[] | null;
+    bid: number | null;
+}
+
+export interface Auction {
+    bidders: Record<string, Bidder>;
+    commit_deadline: number;
+    reveal_deadline: number;
+}
+
+export type AuctionGameEvent = {
+    type: "StateUpdated";
+    payload: { table_id: number; state: Auction | null; events: AuctionChainEvent[] };
+};
+
+export const auctionGameState = reactive({
+    auction: null as Auction | null,
+    settled: null as AuctionChainEvent["AuctionSettled"] | null,
+});
+
+// Sealed bids are only revealed once everyone committed, so the salt has to survive reloads.
+const sealedBidKey = () => `auction_sealed_bid_${gameState.table_id}`;
+
+// Must match `bid_commitment` in the auction contract: sha256(bid as u128 little-endian || salt).
+async function bidCommitment(bid: number, salt: Uint8Array): Promise<number[]> {
+    const data = new Uint8Array(48);
+    new DataView(data.buffer).setBigUint64(0, BigInt(bid), true);
+    data.set(salt, 16);
+    return Array.from(new Uint8Array(await crypto.subtle.digest("SHA-256", data)));
+}
+
+class AuctionGameService extends BaseWebSocketService {
+    protected override onMessage(data: any) {
+        if (data.type === "AuctionGame") {
+            const event: AuctionGameEvent = data.payload;
+            if (event.type === "StateUpdated") {
+                if (event.payload.table_id !== gameState.table_id) return;
+                auctionGameState.auction = event.payload.state;
+                for (const e of event.payload.events) {
+                    if (e.AuctionSettled) {
+                        auctionGameState.settled = e.AuctionSettled;
+                        localStorage.removeItem(sealedBidKey());
+                    }
+                }
+            }
+        }
+    }
+
+    get sealedBid(): number | null {
+        const stored = localStorage.getItem(sealedBidKey());
+        return stored ? JSON.parse(stored).bid : null;
+    }
+
+    async commit(bid: number) {
+        const salt = crypto.getRandomValues(new Uint8Array(32));
+        localStorage.setItem(sealedBidKey(), JSON.stringify({ bid, salt: Array.from(salt) }));
+        auctionGameState.settled = null;
+        await this.send(
+            {
+                type: "AuctionGame",
+                payload: {
+                    type: "Commit",
+                    payload: {
+                        table_id: gameState.table_id,
+                        commitment: await bidCommitment(bid, salt),
+                    },
+                },
+            },
+            "Commit",
+        );
+    }
+
+    async reveal() {
+        const stored = localStorage.getItem(sealedBidKey());
+        if (!stored) return;
+        const { bid, salt } = JSON.parse(stored);
+        await this.send(
+            {
+                type: "AuctionGame",
+                payload: {
+                    type: "Reveal",
+                    payload: { table_id: gameState.table_id, bid, salt },
+                },
+            },
+            "Reveal",
+        );
+    }
+}
+
+export const auctionGameService = new AuctionGameService();
@@ -3,6 +3,17 @@ import { BaseWebSocketService } from "../utils/base-websocket";
 import { boardGameService, gameState, getLocalPlayerId } from "./game_data";
 import { authService } from "./auth";
 
+// Multipliers are sent in basis points, the UI works with plain numbers.
+export const MULTIPLIER_ONE = 10_000;
+
+// Rounds are played side by side, one per board table plus the standalone table.
+export interface RoundKey {
+    board: string | null;
+    table_id: number;
+}
+
+export const STANDALONE_KEY: RoundKey = { board: null, table_id: 0 };
+
 export interface ChainEvent {
     MinigameEnded?: {
         final_results: Array<[string, number]>;
@@ -16,25 +27,77 @@ export type CrashGameCommand =
       }
     | {
           type: "CashOut";
-          payload: { player_id: string };
+          payload: { key: RoundKey; player_id: string };
+      }
+    | {
+          type: "PlaceBet";
+          payload: { key: RoundKey; player_id: string; amount: number };
+      }
+    | {
+          type: "SetAutoCashOut";
+          payload: { key: RoundKey; player_id: string; multiplier: number | null };
       }
     | {
           type: "Start";
           payload: null;
       }
+    | {
+          type: "JoinTable";
+          payload: { player_id: string; name: string; deposit: number };
+      }
+    | {
+          type: "LeaveTable";
+          payload: { player_id: string };
+      }
     | {
           type: "End";
+          payload: { key: RoundKey };
+      }
+    | {
+          type: "GetHistory";
           payload: null;
       };
 
-export type CrashGameEvent = {
-    type: "StateUpdated";
-    payload: { state: CrashGameState | null; events: ChainEvent[] };
-};
+export type CrashGameEvent =
+    | {
+          type: "StateUpdated";
+          payload: {
+              key: RoundKey;
+              round: CrashGameRound | null;
+              seats: Record<string, Seat>;
+              events: ChainEvent[];
+          };
+      }
+    | {
+          type: "Tick";
+          payload: { key: RoundKey; multiplier: number; elapsed_ms: number; server_time: number };
+      }
+    | {
+          type: "History";
+          payload: { history: RoundSummary[] };
+      }
+    | {
+          type: "BetRejected";
+          payload: { player_id: string; event: BetRejection };
+      };
+
+export type BetRejection =
+    | { InsufficientFunds: { player_id: string; available: number; requested: number } }
+    | { InvalidBetAmount: { min: number; max: number; provided: number } };
 
 export interface CrashGameMinigameVerifiableState {
     state: "Uninitialized" | "WaitingForStart" | "Running" | "Crashed";
-    players: Record<string, { id: string; name: string; bet: number; cashed_out_at?: number }>;
+    players: Record<
+        string,
+        {
+            id: string;
+            name: string;
+            bet: number;
+            coins: number;
+            cashed_out_at?: number;
+            auto_cash_out?: number;
+        }
+    >;
 }
 
 export interface CrashGameMinigameBackendState {
@@ -44,7 +107,24 @@ export interface CrashGameMinigameBackendState {
     current_time: number | null;
 }
 
-export interface CrashGameState {
+// A player of the standalone table, playing with deposited oranj.
+export interface Seat {
+    id: string;
+    name: string;
+    balance: number;
+    bet: number;
+}
+
+// A settled round, crash points are converted to plain numbers on reception.
+export interface RoundSummary {
+    key: RoundKey;
+    crash_point: number;
+    player_count: number;
+    total_wagered: number;
+    total_paid: number;
+}
+
+export interface CrashGameRound {
     minigame_verifiable: CrashGameMinigameVerifiableState;
     minigame_backend: CrashGameMinigameBackendState;
 }
@@ -52,19 +132,69 @@ export interface CrashGameState {
 export const crashGameState = reactive({
     minigame_verifiable: null as CrashGameMinigameVerifiableState | null,
     minigame_backend: null as CrashGameMinigameBackendState | null,
+    seats: {} as Record<string, Seat>,
+    // Settled rounds of every table, oldest first.
+    history: [] as RoundSummary[],
+    bet_error: null as string | null,
+    // Whether the standalone table is followed, rather than the round of the local board table.
+    standalone: false,
 });
 
+export function followedKey(): RoundKey {
+    if (crashGameState.standalone) return STANDALONE_KEY;
+    return { board: gameState.board_game_contract, table_id: gameState.table_id };
+}
+
+function isFollowed(key: RoundKey): boolean {
+    const followed = followedKey();
+    return key.board === followed.board && key.table_id === followed.table_id;
+}
+
+// Switches between the standalone table and the board table, forgetting the other round.
+export function followStandalone(standalone: boolean) {
+    crashGameState.standalone = standalone;
+    crashGameState.minigame_verifiable = null;
+    crashGameState.minigame_backend = null;
+}
+
 class CrashGameService extends BaseWebSocketService {
     protected override onMessage(data: any) {
         if (data.type === "CrashGame") {
             const event = data.payload;
             if (event.type === "StateUpdated") {
-                const state = event.payload.state;
-                if (state) {
-                    crashGameState.minigame_verifiable = state.minigame_verifiable;
-                    crashGameState.minigame_backend = state.minigame_backend;
+                crashGameState.seats = event.payload.seats;
+                if (!isFollowed(event.payload.key)) return;
+                const round = event.payload.round;
+                if (round) {
+                    for (const player of Object.values(round.minigame_verifiable.players) as any[]) {
+                        if (player.cashed_out_at) player.cashed_out_at /= MULTIPLIER_ONE;
+                        if (player.auto_cash_out) player.auto_cash_out /= MULTIPLIER_ONE;
+                    }
+                    round.minigame_backend.current_multiplier /= MULTIPLIER_ONE;
+                    crashGameState.minigame_verifiable = round.minigame_verifiable;
+                    crashGameState.minigame_backend = round.minigame_backend;
+                } else if (crashGameState.minigame_verifiable) {
+                    // The round is over once settled, like an uninitialized game.
+                    crashGameState.minigame_verifiable = { state: "Uninitialized", players: {} };
+                }
+            } else if (event.type === "Tick") {
+                // Lightweight updates while the rocket flies, the full state comes with on-chain changes.
+                if (!crashGameState.minigame_backend || !isFollowed(event.payload.key)) return;
+                crashGameState.minigame_backend.current_multiplier = event.payload.multiplier / MULTIPLIER_ONE;
+                crashGameState.minigame_backend.current_time = event.payload.server_time;
+            } else if (event.type === "History") {
+                crashGameState.history = event.payload.history.map((round: RoundSummary) => ({
+                    ...round,
+                    crash_point: round.crash_point / MULTIPLIER_ONE,
+                }));
+            } else if (event.type === "BetRejected") {
+                if (event.payload.player_id !== getLocalPlayerId()) return;
+                const rejection: BetRejection = event.payload.event;
+                if ("InsufficientFunds" in rejection) {
+                    crashGameState.bet_error = `You only have ${rejection.InsufficientFunds.available} coins`;
                 } else {
-                    console.log("Crash game state cleared");
+                    const { min, max } = rejection.InvalidBetAmount;
+                    crashGameState.bet_error = `Bets must be between ${min} and ${max} coins`;
                 }
             }
         }
@@ -77,6 +207,7 @@ class CrashGameService extends BaseWebSocketService {
                 payload: {
                     type: "CashOut",
                     payload: {
+                        key: followedKey(),
                         player_id: getLocalPlayerId(),
                     },
                 },
@@ -85,13 +216,96 @@ class CrashGameService extends BaseWebSocketService {
         );
     }
 
+    placeBet(amount: number) {
+        crashGameState.bet_error = null;
+        this.send(
+            {
+                type: "CrashGame",
+                payload: {
+                    type: "PlaceBet",
+                    payload: {
+                        key: followedKey(),
+                        player_id: getLocalPlayerId(),
+                        amount,
+                    },
+                },
+            },
+            "PlaceBet",
+        );
+    }
+
+    // Cashes out automatically if the game crashes above `multiplier`, or never if null.
+    setAutoCashOut(multiplier: number | null) {
+        this.send(
+            {
+                type: "CrashGame",
+                payload: {
+                    type: "SetAutoCashOut",
+                    payload: {
+                        key: followedKey(),
+                        player_id: getLocalPlayerId(),
+                        multiplier: multiplier === null ? null : Math.round(multiplier * MULTIPLIER_ONE),
+                    },
+                },
+            },
+            "SetAutoCashOut",
+        );
+    }
+
+    // Sits at the standalone table, or tops up the seat, with `deposit` oranj.
+    joinTable(name: string, deposit: number) {
+        this.send(
+            {
+                type: "CrashGame",
+                payload: {
+                    type: "JoinTable",
+                    payload: {
+                        player_id: getLocalPlayerId(),
+                        name,
+                        deposit,
+                    },
+                },
+            },
+            "JoinTable",
+        );
+    }
+
+    // Leaves the standalone table, the balance is sent back in oranj.
+    leaveTable() {
+        this.send(
+            {
+                type: "CrashGame",
+                payload: {
+                    type: "LeaveTable",
+                    payload: {
+                        player_id: getLocalPlayerId(),
+                    },
+                },
+            },
+            "LeaveTable",
+        );
+    }
+
+    fetchHistory() {
+        this.send(
+            {
+                type: "CrashGame",
+                payload: {
+                    type: "GetHistory",
+                    payload: null,
+                },
+            },
+            "GetHistory",
+        );
+    }
+
     returnToBoard() {
         this.send(
             {
                 type: "CrashGame",
                 payload: {
                     type: "End",
-                    payload: null,
+                    payload: { key: followedKey() },
                 },
             },
             "EndMinigame",
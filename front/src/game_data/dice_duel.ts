@@ -0,0 +1,73 @@
+import { reactive } from "vue";
+import { BaseWebSocketService } from "../utils/base-websocket";
+import { gameState } from "./game_data";
+
+export interface DiceDuelChainEvent {
+    MinigameInitialized?: {
+        bracket: string[];
+    };
+    DuelResolved?: {
+        round: number;
+        winner: string;
+        loser: string;
+        rolls: Array<[number, number]>;
+        stake: number;
+    };
+    ByeGranted?: {
+        round: number;
+        player_id: string;
+    };
+    MinigameEnded?: {
+        final_results: Array<[string, number]>;
+    };
+}
+
+export interface Duelist {
+    id: string;
+    name: string;
+    ante: number;
+    stake: number;
+}
+
+export interface Bracket {
+    duelists: Record<string, Duelist>;
+    remaining: string[];
+    round: number;
+    next_round_at: number;
+}
+
+export type DiceDuelEvent = {
+    type: "StateUpdated";
+    payload: { table_id: number; state: Bracket | null; events: DiceDuelChainEvent[] };
+};
+
+export const diceDuelState = reactive({
+    bracket: null as Bracket | null,
+    // Duels of the bracket so far, most recent last.
+    history: [] as DiceDuelChainEvent[],
+    final_results: null as Array<[string, number]> | null,
+});
+
+// Rounds are played by the backend, players only watch.
+class DiceDuelService extends BaseWebSocketService {
+    protected override onMessage(data: any) {
+        if (data.type === "DiceDuel") {
+            const event: DiceDuelEvent = data.payload;
+            if (event.type === "StateUpdated") {
+                if (event.payload.table_id !== gameState.table_id) return;
+                // The last state is kept once the bracket is over, to show the results.
+                diceDuelState.bracket = event.payload.state ?? diceDuelState.bracket;
+                for (const e of event.payload.events) {
+                    if (e.MinigameInitialized) {
+                        diceDuelState.history = [];
+                        diceDuelState.final_results = null;
+                    }
+                    if (e.DuelResolved || e.ByeGranted) diceDuelState.history.push(e);
+                    if (e.MinigameEnded) diceDuelState.final_results = e.MinigameEnded.final_results;
+                }
+            }
+        }
+    }
+}
+
+export const diceDuelService = new DiceDuelService();
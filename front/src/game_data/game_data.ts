@@ -24,6 +24,7 @@ export interface Player {
     name: string;
     position: number;
     coins: number;
+    deposit: number;
     used_uuids: Array<bigint>;
 }
 
@@ -31,29 +32,36 @@ export type GamePhase =
     | "Registration"
     | "Betting"
     | "WheelSpin"
+    | "BoardMoves"
     | { StartMinigame: string }
     | { InMinigame: string }
     | "FinalMinigame"
     | "RewardsDistribution"
     | "GameOver";
 
-export type MinigameSetup = Array<[string, string, number]>;
+// (player id, name, bet, board coins)
+export type MinigameSetup = Array<[string, string, number, number]>;
 
 export type GameAction =
     | { EndGame: null }
     | {
           Initialize: {
               minigames: string[];
-              random_seed: number;
+              seed_commitment?: number[];
+              config?: GameConfig;
           };
       }
-    | { RegisterPlayer: { name: string; deposit: number } }
+    | { RegisterPlayer: { name: string; deposit: number; entropy?: number } }
+    | { RevealSeed: { seed: number[] } }
     | { StartGame: null }
     | { PlaceBet: { amount: number } }
     | { SpinWheel: null }
-    | { StartMinigame: { minigame: string; players: MinigameSetup } }
+    | { StartMinigame: { minigame: string; players: MinigameSetup; seed?: number[] | null } }
     | { EndMinigame: { result: MinigameResult } }
-    | { EndTurn: null };
+    | { RollDice: null }
+    | { EndTurn: null }
+    | { LeaveGame: null }
+    | { AdvancePhase: null };
 
 export type GameEvent =
     | { DiceRolled: { player_id: string; value: number } }
@@ -64,16 +72,45 @@ export type GameEvent =
     | { MinigameEnded: { result: MinigameResult } }
     | { TurnEnded: { next_player: string } }
     | { GameEnded: { winner_id: string; final_coins: number } }
-    | { GameInitialized: { random_seed: number } }
+    | { GameInitialized: { seed_commitment: number[] } }
+    | { SeedRevealed: { seed: number[] } }
     | { PlayerRegistered: { name: string; player_id: string } }
     | { GameStarted: { player_count: number } }
     | { BetPlaced: { player_id: string; amount: number } }
-    | { WheelSpun: { round: number; outcome: number } };
+    | { WheelSpun: { round: number; outcome: number; effect: WheelEffect } }
+    | { PlayersSwappedCoins: { swaps: Array<[string, string]> } }
+    | { AllOrNothingActivated: null }
+    | { TaxCollected: { percent: number } }
+    | { BonusAwarded: { amount: number } }
+    | "BetsDoubled"
+    | { RoundSkipped: { round: number } }
+    | { PlayerLeft: { player_id: string; refund: number } }
+    | { PhaseAdvanced: { player_id: string } }
+    | "SeedRevealTimedOut"
+    | { LandedOnTile: { player_id: string; position: number; tile: Tile } };
+
+export type WheelEffect =
+    | "Nothing"
+    | "RandomPayout"
+    | "AllOrNothing"
+    | "Minigame"
+    | "CoinSwap"
+    | { Tax: { percent: number } }
+    | { Bonus: { amount: number } }
+    | "DoubleBets"
+    | "SkipRound";
+
+export interface WheelSegment {
+    weight: number;
+    effect: WheelEffect;
+}
+
+export type Tile = "Start" | { Coins: number } | "Minigame" | "Swap" | { Shortcut: { to: number } };
 
 export type GameStateCommand =
     | {
           type: "SubmitAction";
-          payload: { action: GameAction };
+          payload: { table_id: number; action: GameAction };
       }
     | {
           type: "SendState";
@@ -83,7 +120,7 @@ export type GameStateCommand =
 export type GameStateEvent =
     | {
           type: "StateUpdated";
-          payload: { state: GameState | null; events: GameEvent[] };
+          payload: { table_id: number; state: GameState | null; events: GameEvent[] };
       }
     | {
           type: "MinigameStarted";
@@ -94,22 +131,53 @@ export type GameStateEvent =
           payload: { result: MinigameResult };
       };
 
+export interface GameConfig {
+    rounds: number;
+    max_players: number;
+    registration_window_ms: number;
+    betting_window_ms: number;
+    turn_timeout_ms: number;
+    no_bet_penalty: number;
+    max_deposit: number;
+    minigame_weights: number[];
+    final_minigame: string | null;
+    no_repeat: boolean;
+    wheel: WheelSegment[];
+    payout: PayoutPolicy;
+    house_fee_bps: number;
+}
+
+export type PayoutPolicy = "WinnerTakesAll" | { TopN: { shares_bps: number[] } } | "Proportional";
+
 export interface GameState {
     players: Player[];
     phase: GamePhase;
-    max_players: number;
+    config: GameConfig;
     minigames: string[];
-    dice: { min: number; max: number; seed: number };
+    dice: { min: number; max: number; state: number[] };
+    seed_commitment: number[];
+    seed_nonce: number;
+    entropy: number[];
+    seed_revealed: boolean;
+    reveal_deadline: number;
     round_started_at: number;
+    deadline: number;
     round: number;
     bets: Record<string, number>;
-    backend_identity: string;
     last_interaction_time: number;
-    lane_id: string;
     all_or_nothing?: boolean;
+    board: { tiles: Tile[] };
+    turn: number;
+    has_rolled: boolean;
+    pending_minigame: boolean;
+    last_minigame: string | null;
 }
 
+// Each browser plays at a single table, picked with the `?table=` query parameter.
+const currentTableId = Number(new URLSearchParams(window.location.search).get("table") ?? 0) || 0;
+
 export const gameState = reactive({
+    table_id: currentTableId,
     game: null as GameState | null,
     running_minigame: null as string | null,
     isInLobby: true,
@@ -117,6 +185,10 @@ export const gameState = reactive({
 
     board_game_contract: "",
     crash_game_contract: "",
+    auction_game_contract: "",
+    dice_duel_contract: "",
+    resource_split_contract: "",
+    lottery_contract: "",
 });
 
 class BoardGameService extends BaseWebSocketService {
@@ -140,6 +212,7 @@ class BoardGameService extends BaseWebSocketService {
         if (data.type === "GameStateEvent") {
             const event = data.payload;
             if (event.type === "StateUpdated") {
+                if (event.payload.table_id !== gameState.table_id) return;
                 gameState.game = event.payload.state;
                 if (gameState.game?.players.length === 0) gameState.isInLobby = true;
                 else if (
@@ -155,6 +228,10 @@ class BoardGameService extends BaseWebSocketService {
                     console.log("Board game contract", event.payload.board_game);
                     gameState.board_game_contract = event.payload.board_game;
                     gameState.crash_game_contract = event.payload.crash_game;
+                    gameState.auction_game_contract = event.payload.auction_game;
+                    gameState.dice_duel_contract = event.payload.dice_duel;
+                    gameState.resource_split_contract = event.payload.resource_split;
+                    gameState.lottery_contract = event.payload.lottery;
                 }
                 for (const e of event.payload.events) {
                     if (e instanceof Object && "MinigameReady" in e) {
@@ -164,6 +241,7 @@ class BoardGameService extends BaseWebSocketService {
                                 payload: {
                                     type: "SubmitAction",
                                     payload: {
+                                        table_id: gameState.table_id,
                                         action: {
                                             // Replaced in the backend
                                             StartMinigame: { minigame: "", players: [] },
@@ -193,7 +271,7 @@ class BoardGameService extends BaseWebSocketService {
                 type: "GameState",
                 payload: {
                     type: "SubmitAction",
-                    payload: { action },
+                    payload: { table_id: gameState.table_id, action },
                 },
             },
             `${Object.keys(action)[0]}`,
@@ -207,10 +285,10 @@ class BoardGameService extends BaseWebSocketService {
                 payload: {
                     type: "SubmitAction",
                     payload: {
+                        table_id: gameState.table_id,
                         action: {
                             Initialize: {
                                 minigames: [], // will be overwritten by the server
-                                random_seed: 7, // will be overwritten by the server
                             },
                         },
                     },
@@ -227,10 +305,13 @@ class BoardGameService extends BaseWebSocketService {
                 payload: {
                     type: "SubmitAction",
                     payload: {
+                        table_id: gameState.table_id,
                         action: {
                             RegisterPlayer: {
                                 name,
                                 deposit,
+                                // Our share of the dice randomness.
+                                entropy: crypto.getRandomValues(new Uint32Array(1))[0],
                             },
                         },
                     },
@@ -247,6 +328,7 @@ class BoardGameService extends BaseWebSocketService {
                 payload: {
                     type: "SubmitAction",
                     payload: {
+                        table_id: gameState.table_id,
                         action: { StartGame: null },
                     },
                 },
@@ -255,6 +337,39 @@ class BoardGameService extends BaseWebSocketService {
         );
     }
 
+    async leaveGame() {
+        await this.send(
+            {
+                type: "GameState",
+                payload: {
+                    type: "SubmitAction",
+                    payload: {
+                        table_id: gameState.table_id,
+                        action: { LeaveGame: null },
+                    },
+                },
+            },
+            "LeaveGame",
+        );
+    }
+
+    // Moves the table along once the current phase is overdue, without waiting for the server.
+    async advancePhase() {
+        await this.send(
+            {
+                type: "GameState",
+                payload: {
+                    type: "SubmitAction",
+                    payload: {
+                        table_id: gameState.table_id,
+                        action: { AdvancePhase: null },
+                    },
+                },
+            },
+            "AdvancePhase",
+        );
+    }
+
     async endGame() {
         await this.send(
             {
@@ -262,6 +377,7 @@ class BoardGameService extends BaseWebSocketService {
                 payload: {
                     type: "SubmitAction",
                     payload: {
+                        table_id: gameState.table_id,
                         action: { EndGame: null },
                     },
                 },
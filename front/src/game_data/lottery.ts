@@ -0,0 +1,76 @@
+import { reactive } from "vue";
+import { BaseWebSocketService } from "../utils/base-websocket";
+import { gameState } from "./game_data";
+
+export interface LotteryChainEvent {
+    MinigameInitialized?: {
+        tickets: number;
+    };
+    Drawn?: {
+        pot_winner: string | null;
+        prize: number;
+        jackpot_winner: string | null;
+        jackpot_won: number;
+        jackpot: number;
+    };
+    MinigameEnded?: {
+        final_results: Array<[string, number]>;
+    };
+}
+
+export interface TicketHolder {
+    id: string;
+    name: string;
+    stake: number;
+}
+
+export interface Draw {
+    holders: Record<string, TicketHolder>;
+    draw_at: number;
+    seed_index: number;
+}
+
+export type LotteryEvent =
+    | {
+          type: "StateUpdated";
+          payload: { table_id: number; state: Draw | null; jackpot: number; events: LotteryChainEvent[] };
+      }
+    | {
+          type: "Jackpot";
+          payload: { jackpot: number };
+      };
+
+export const lotteryState = reactive({
+    draw: null as Draw | null,
+    // The jackpot is shared by every table.
+    jackpot: 0,
+    outcome: null as LotteryChainEvent["Drawn"] | null,
+    final_results: null as Array<[string, number]> | null,
+});
+
+// Draws are made by the backend, players only watch.
+class LotteryService extends BaseWebSocketService {
+    protected override onMessage(data: any) {
+        if (data.type === "Lottery") {
+            const event: LotteryEvent = data.payload;
+            if (event.type === "Jackpot") {
+                lotteryState.jackpot = event.payload.jackpot;
+            } else if (event.type === "StateUpdated") {
+                lotteryState.jackpot = event.payload.jackpot;
+                if (event.payload.table_id !== gameState.table_id) return;
+                // The last state is kept once the draw is over, to show the tickets.
+                lotteryState.draw = event.payload.state ?? lotteryState.draw;
+                for (const e of event.payload.events) {
+                    if (e.MinigameInitialized) {
+                        lotteryState.outcome = null;
+                        lotteryState.final_results = null;
+                    }
+                    if (e.Drawn) lotteryState.outcome = e.Drawn;
+                    if (e.MinigameEnded) lotteryState.final_results = e.MinigameEnded.final_results;
+                }
+            }
+        }
+    }
+}
+
+export const lotteryService = new LotteryService();
@@ -0,0 +1,114 @@
+import { reactive } from "vue";
+import { BaseWebSocketService } from "../utils/base-websocket";
+import { gameState } from "./game_data";
+
+export type Choice = "Share" | "Hoard";
+
+export interface ResourceSplitChainEvent {
+    ResourcesSplit?: {
+        sharers: number;
+        hoarders: number;
+        pot: number;
+    };
+    MinigameEnded?: {
+        final_results: Array<[string, number]>;
+    };
+}
+
+export interface Colonist {
+    id: string;
+    name: string;
+    ante: number;
+    commitment: number[] | null;
+    choice: Choice | null;
+}
+
+export interface Split {
+    colonists: Record<string, Colonist>;
+    commit_deadline: number;
+    reveal_deadline: number;
+}
+
+export type ResourceSplitEvent = {
+    type: "StateUpdated";
+    payload: { table_id: number; state: Split | null; events: ResourceSplitChainEvent[] };
+};
+
+export const resourceSplitState = reactive({
+    split: null as Split | null,
+    outcome: null as ResourceSplitChainEvent["ResourcesSplit"] | null,
+    final_results: null as Array<[string, number]> | null,
+});
+
+// Choices are only revealed once everyone committed, so the salt has to survive reloads.
+const sealedChoiceKey = () => `resource_split_sealed_choice_${gameState.table_id}`;
+
+// Must match `choice_commitment` in the resource split contract: sha256(choice byte || salt).
+async function choiceCommitment(choice: Choice, salt: Uint8Array): Promise<number[]> {
+    const data = new Uint8Array(33);
+    data[0] = choice === "Share" ? 0 : 1;
+    data.set(salt, 1);
+    return Array.from(new Uint8Array(await crypto.subtle.digest("SHA-256", data)));
+}
+
+class ResourceSplitService extends BaseWebSocketService {
+    protected override onMessage(data: any) {
+        if (data.type === "ResourceSplit") {
+            const event: ResourceSplitEvent = data.payload;
+            if (event.type === "StateUpdated") {
+                if (event.payload.table_id !== gameState.table_id) return;
+                resourceSplitState.split = event.payload.state ?? resourceSplitState.split;
+                for (const e of event.payload.events) {
+                    if (e.ResourcesSplit) {
+                        resourceSplitState.outcome = e.ResourcesSplit;
+                        localStorage.removeItem(sealedChoiceKey());
+                    }
+                    if (e.MinigameEnded) resourceSplitState.final_results = e.MinigameEnded.final_results;
+                }
+            }
+        }
+    }
+
+    get sealedChoice(): Choice | null {
+        const stored = localStorage.getItem(sealedChoiceKey());
+        return stored ? JSON.parse(stored).choice : null;
+    }
+
+    async commit(choice: Choice) {
+        const salt = crypto.getRandomValues(new Uint8Array(32));
+        localStorage.setItem(sealedChoiceKey(), JSON.stringify({ choice, salt: Array.from(salt) }));
+        resourceSplitState.outcome = null;
+        resourceSplitState.final_results = null;
+        await this.send(
+            {
+                type: "ResourceSplit",
+                payload: {
+                    type: "Commit",
+                    payload: {
+                        table_id: gameState.table_id,
+                        commitment: await choiceCommitment(choice, salt),
+                    },
+                },
+            },
+            "Commit",
+        );
+    }
+
+    async reveal() {
+        const stored = localStorage.getItem(sealedChoiceKey());
+        if (!stored) return;
+        const { choice, salt } = JSON.parse(stored);
+        await this.send(
+            {
+                type: "ResourceSplit",
+                payload: {
+                    type: "Reveal",
+                    payload: { table_id: gameState.table_id, choice, salt },
+                },
+            },
+            "Reveal",
+        );
+    }
+}
+
+export const resourceSplitService = new ResourceSplitService();
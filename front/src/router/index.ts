@@ -1,5 +1,10 @@
 import { createRouter, createWebHistory } from "vue-router";
 import CrashGame from "../components/CrashGame.vue";
+import CrashTable from "../components/CrashTable.vue";
+import AuctionGame from "../components/AuctionGame.vue";
+import DiceDuel from "../components/DiceDuel.vue";
+import ResourceSplit from "../components/ResourceSplit.vue";
+import Lottery from "../components/Lottery.vue";
 import Board from "../components/Board.vue";
 import Lobby from "../components/Lobby.vue";
 import { watchEffect } from "vue";
@@ -12,6 +17,31 @@ const routes = [
         name: "CrashGame",
         component: CrashGame,
     },
+    {
+        path: "/crash-table",
+        name: "CrashTable",
+        component: CrashTable,
+    },
+    {
+        path: "/auction",
+        name: "AuctionGame",
+        component: AuctionGame,
+    },
+    {
+        path: "/dice-duel",
+        name: "DiceDuel",
+        component: DiceDuel,
+    },
+    {
+        path: "/resource-split",
+        name: "ResourceSplit",
+        component: ResourceSplit,
+    },
+    {
+        path: "/lottery",
+        name: "Lottery",
+        component: Lottery,
+    },
     {
         path: "/board",
         name: "Board",
@@ -31,8 +61,18 @@ const router = createRouter({
 });
 
 watchEffect(() => {
+    // The standalone crash table is not part of a board game.
+    if (router.currentRoute.value.name === "CrashTable") return;
     if (gameState.isInLobby) {
         router.push({ name: "Lobby" });
+    } else if (gameState.isInMinigame && gameState.running_minigame === gameState.auction_game_contract) {
+        router.push({ name: "AuctionGame" });
+    } else if (gameState.isInMinigame && gameState.running_minigame === gameState.dice_duel_contract) {
+        router.push({ name: "DiceDuel" });
+    } else if (gameState.isInMinigame && gameState.running_minigame === gameState.resource_split_contract) {
+        router.push({ name: "ResourceSplit" });
+    } else if (gameState.isInMinigame && gameState.running_minigame === gameState.lottery_contract) {
+        router.push({ name: "Lottery" });
     } else if (gameState.isInMinigame) {
         router.push({ name: "CrashGame" });
     } else {